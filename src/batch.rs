@@ -0,0 +1,194 @@
+use crate::cli::{Args,OutputFormat};
+use crate::{parse_character,want_xp,select_attacks,write_sheet,output_path,cache};
+use dicecloud_sheet_printer::{DicecloudClient,holding_structs::{Attack,Character},sheet::{render_party,render_party_summary,SheetOptions}};
+use futures::stream::{self,StreamExt};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::{Path,PathBuf};
+use std::{fs,process};
+
+///how many characters are downloaded at once
+const CONCURRENT_DOWNLOADS: usize = 4;
+
+///what happened to one character of a batch
+enum Outcome{
+    Written{name: String, path: String, warnings: usize},
+    Failed(String),
+}
+///prints every character in `ids` into the output folder, one file each, or into one party pdf with `--combine`,
+///and the party summary with `--dm-summary`.
+///Nothing is prompted for. Downloads happen concurrently, then the sheets are rendered one at a time and a report of
+///what happened to each character is printed. Exits with 1 if any of them failed
+pub async fn run(args: &Args, client: &DicecloudClient, ids: &[String], race_decoder: &Value){
+    if args.combine && args.format!=OutputFormat::Pdf{
+        eprintln!("--combine only works with --format pdf");
+        process::exit(2);
+    }
+    let party_path = if args.combine {Some(output_path(args, false, "party"))} else {None};
+    let dir = args.output.clone().filter(|_| !args.combine).unwrap_or_else(|| "./sheet_outputs".to_string());
+    let folder = match &party_path{
+        Some(path)=>Path::new(path).parent().map(Path::to_path_buf).unwrap_or_default(),
+        None=>PathBuf::from(&dir),
+    };
+    if let Err(e)=fs::create_dir_all(&folder){
+        eprintln!("Failed to create {}: {}",folder.display(),e);
+        process::exit(1);
+    }
+    println!("Getting {} characters",ids.len());
+    let fetched: Vec<_> = stream::iter(ids)
+        .map(|id| async move {(id, cache::creature(client, id, args.offline).await)})
+        .buffered(CONCURRENT_DOWNLOADS)
+        .collect()
+        .await;
+    let mut used_paths = HashSet::new();
+    let mut party = Vec::new();
+    let mut summary = Vec::new();
+    let mut report = Vec::new();
+    for (id, res) in fetched{
+        let prepared = match res{
+            Ok(char_json)=>{
+                println!("Processing {}...",id);
+                prepare(args, char_json, race_decoder)
+            },
+            Err(e)=>Err(format!("Failed to get the character: {}",e)),
+        };
+        if let (Ok(sheet), true) = (&prepared, args.dm_summary){
+            summary.push(sheet.character.clone());
+        }
+        let outcome = match (prepared, &party_path){
+            (Err(e), _)=>Outcome::Failed(e),
+            (Ok(sheet), None)=>{
+                let path = output_file(&dir, &args.format.default_name(&sheet.character.char_name), id, args.format.extension(), &mut used_paths);
+                match write_sheet(args, client, &sheet.character, sheet.include_xp, sheet.attacks, &path).await{
+                    Ok(())=>Outcome::Written{name: sheet.character.char_name, path, warnings: sheet.warnings},
+                    Err(e)=>Outcome::Failed(e),
+                }
+            },
+            (Ok(sheet), Some(_))=>{
+                let options = SheetOptions{
+                    include_xp: sheet.include_xp,
+                    attacks: sheet.attacks,
+                    portrait: cache::portrait(client, &sheet.character.char_img, args.offline).await,
+                    spell_descriptions: args.spell_descriptions,
+                    feature_descriptions: args.feature_descriptions,
+                    variant_encumbrance: args.variant_encumbrance,
+                };
+                let outcome = Outcome::Written{name: sheet.character.char_name.clone(), path: String::new(), warnings: sheet.warnings};
+                party.push((sheet.character, options));
+                outcome
+            },
+        };
+        report.push((id, outcome));
+    }
+    if let (Some(path), false) = (&party_path, party.is_empty()){
+        println!("Setting up document...");
+        let sheets: Vec<_> = party.iter().map(|(character, options)| (character, options)).collect();
+        let written = render_party(&sheets)
+            .map_err(|e| format!("Failed to lay out the party sheet: {}",e))
+            .and_then(|doc| {
+                println!("Rendering pdf...(this may take a moment)");
+                doc.render_to_file(path).map_err(|e| format!("Failed to write {}: {}",path,e))
+            });
+        for (_, outcome) in report.iter_mut(){
+            match (outcome, &written){
+                (Outcome::Written{path: page_path, ..}, Ok(()))=>*page_path = path.clone(),
+                (outcome @ Outcome::Written{..}, Err(e))=>*outcome = Outcome::Failed(e.clone()),
+                _=>(),
+            }
+        }
+    }
+    let summary_written = if args.dm_summary && !summary.is_empty(){
+        let path = folder.join("party_summary.pdf").to_string_lossy().to_string();
+        let characters: Vec<&Character> = summary.iter().collect();
+        let written = render_party_summary(&characters)
+            .map_err(|e| format!("Failed to lay out the party summary: {}",e))
+            .and_then(|doc| doc.render_to_file(&path).map_err(|e| format!("Failed to write {}: {}",path,e)));
+        Some(written.map(|()| path))
+    } else {
+        None
+    };
+    let failed = report.iter().filter(|(_, outcome)| matches!(outcome,Outcome::Failed(_))).count();
+    println!("Printed {} of {} characters",report.len()-failed,report.len());
+    for (id, outcome) in report{
+        match outcome{
+            Outcome::Written{name, path, warnings: 0}=>println!("  ok     {} ({}): {}",name,id,path),
+            Outcome::Written{name, path, warnings}=>println!("  ok     {} ({}): {}, {} properties left off",name,id,path,warnings),
+            Outcome::Failed(e)=>println!("  failed {}: {}",id,e),
+        }
+    }
+    match &summary_written{
+        Some(Ok(path))=>println!("Party summary: {}",path),
+        Some(Err(e))=>println!("Party summary failed: {}",e),
+        None=>(),
+    }
+    if failed>0 || matches!(summary_written,Some(Err(_))){
+        process::exit(1);
+    }
+}
+///a character ready to be written
+struct PreparedSheet{
+    character: Character,
+    warnings: usize,
+    include_xp: bool,
+    attacks: Option<Vec<Attack>>,
+}
+///reads the character and picks what goes on its sheet, without prompting
+fn prepare(args: &Args, char_json: Value, race_decoder: &Value)->Result<PreparedSheet,String>{
+    let (character, warnings) = parse_character(args, char_json, race_decoder.clone())
+        .map_err(|e| format!("Failed to read the character: {}",e))?;
+    let attacks = if args.format==OutputFormat::Pdf{
+        Some(select_attacks(args, false, &character)?)
+    } else {
+        None
+    };
+    Ok(PreparedSheet{
+        include_xp: want_xp(args, false, &character),
+        warnings: warnings.len(),
+        character,
+        attacks,
+    })
+}
+///the character name made safe to use as a file name: characters that aren't allowed in file names on some system are
+///replaced with `_`, and a name made only of dots is dropped so it can't leave the output folder. Can be empty
+pub fn file_name(char_name: &str)->String{
+    let name: String = char_name.trim().chars().map(|c| if "/\\:*?\"<>|".contains(c) || c.is_control() {'_'} else {c}).collect();
+    if name.chars().all(|c| c=='.') {String::new()} else {name}
+}
+///`<dir>/<character name><ext>`, with the id added if another character in the batch has the same name
+fn output_file(dir: &str, char_name: &str, id: &str, ext: &str, used_paths: &mut HashSet<String>)->String{
+    let name = file_name(char_name);
+    let name = if name.is_empty() {id.to_string()} else {name};
+    let mut path = Path::new(dir).join(format!("{}{}",name,ext)).to_string_lossy().to_string();
+    if used_paths.contains(&path){
+        path = Path::new(dir).join(format!("{} ({}){}",name,id,ext)).to_string_lossy().to_string();
+    }
+    used_paths.insert(path.clone());
+    path
+}
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn file_name_replaces_unsafe_characters(){
+        assert_eq!(file_name(" Bob/Alice: the \"Great\"? "), "Bob_Alice_ the _Great__");
+        assert_eq!(file_name(".."), "");
+        assert_eq!(file_name("Dr. Who"), "Dr. Who");
+    }
+    #[test]
+    fn output_file_adds_the_id_to_repeated_names(){
+        let mut used = HashSet::new();
+        let dir = Path::new("out");
+        assert_eq!(output_file("out", "Bob", "abc", ".pdf", &mut used), dir.join("Bob.pdf").to_string_lossy());
+        assert_eq!(output_file("out", "Bob", "def", ".pdf", &mut used), dir.join("Bob (def).pdf").to_string_lossy());
+        assert_eq!(output_file("out", "Alice", "ghi", ".pdf", &mut used), dir.join("Alice.pdf").to_string_lossy());
+    }
+    #[test]
+    fn output_file_stays_in_the_folder(){
+        let mut used = HashSet::new();
+        let dir = Path::new("out");
+        assert_eq!(output_file("out", "../../etc/passwd", "abc", ".pdf", &mut used), dir.join(".._.._etc_passwd.pdf").to_string_lossy());
+        assert_eq!(output_file("out", "..", "abc", ".pdf", &mut used), dir.join("abc.pdf").to_string_lossy());
+        assert_eq!(output_file("out", "", "def", ".pdf", &mut used), dir.join("def.pdf").to_string_lossy());
+    }
+}
@@ -0,0 +1,214 @@
+use dicecloud_sheet_printer::{ClientError,DicecloudClient,portrait_from_bytes};
+use serde_json::Value;
+use std::path::{Path,PathBuf};
+use std::time::{Duration,SystemTime};
+use std::{fmt,fs,io};
+
+///The last successful downloads of each character and portrait, kept in the user cache directory so sheets can be
+///printed when dicecloud can't be reached. A file's modification time is when it was downloaded
+pub struct Cache{
+    dir: PathBuf,
+}
+///Something read from the cache, with how long ago it was downloaded
+pub struct Cached<T>{
+    pub value: T,
+    pub age: Duration,
+}
+impl Cache{
+    ///the cache in the printer's folder of the user cache directory, eg `~/.cache/dicecloud_sheet_printer` on linux
+    pub fn open()->Option<Cache>{
+        dirs::cache_dir().map(|dir| Cache{dir: dir.join("dicecloud_sheet_printer")})
+    }
+    fn creature_path(&self, id: &str)->PathBuf{
+        self.dir.join("creatures").join(format!("{}.json",id))
+    }
+    ///portraits are keyed by url, hashed to make a file name
+    fn portrait_path(&self, url: &str)->PathBuf{
+        self.dir.join("portraits").join(format!("{:016x}",fnv1a(url)))
+    }
+    ///the last creature json downloaded for the character id
+    pub fn creature(&self, id: &str)->Option<Cached<Value>>{
+        let (bytes, age) = read(&self.creature_path(id))?;
+        let value = serde_json::from_slice(&bytes).ok()?;
+        Some(Cached{value, age})
+    }
+    ///whether there is a copy of the character to fall back on
+    pub fn has_creature(&self, id: &str)->bool{
+        self.creature_path(id).is_file()
+    }
+    pub fn store_creature(&self, id: &str, creature: &Value)->io::Result<()>{
+        write(&self.creature_path(id), &serde_json::to_vec(creature)?)
+    }
+    ///the last portrait downloaded from the url, as downloaded
+    pub fn portrait(&self, url: &str)->Option<Cached<Vec<u8>>>{
+        read(&self.portrait_path(url)).map(|(value, age)| Cached{value, age})
+    }
+    pub fn store_portrait(&self, url: &str, bytes: &[u8])->io::Result<()>{
+        write(&self.portrait_path(url), bytes)
+    }
+}
+///Why a character could not be fetched
+#[derive(Debug)]
+pub enum FetchError{
+    ///the download failed and there is no cached copy to fall back on
+    Client(ClientError),
+    ///printing offline, but the character was never downloaded
+    NotCached(String),
+}
+impl fmt::Display for FetchError{
+    fn fmt(&self, f: &mut fmt::Formatter)->fmt::Result{
+        match self{
+            FetchError::Client(e)=>write!(f,"{}",e),
+            FetchError::NotCached(id)=>write!(f,"{} has not been downloaded before, so there is no copy to print offline",id),
+        }
+    }
+}
+///downloads the creature json for the character id and caches it. With `offline`, or when dicecloud can't be
+///reached, the cached copy is used instead, with a note saying how old it is
+pub async fn creature(client: &DicecloudClient, id: &str, offline: bool)->Result<Value,FetchError>{
+    creature_in(Cache::open(), client, id, offline).await
+}
+async fn creature_in(cache: Option<Cache>, client: &DicecloudClient, id: &str, offline: bool)->Result<Value,FetchError>{
+    if offline{
+        let cached = cache.as_ref().and_then(|cache| cache.creature(id)).ok_or_else(|| FetchError::NotCached(id.to_string()))?;
+        println!("Using the copy of {} downloaded {}",id,describe_age(cached.age));
+        return Ok(cached.value);
+    }
+    match client.creature(id).await{
+        Ok(json)=>{
+            if let Some(cache)=&cache{
+                if let Err(e)=cache.store_creature(id, &json){
+                    println!("Failed to cache {}: {}",id,e);
+                }
+            }
+            Ok(json)
+        },
+        Err(ClientError::Http(e))=>match cache.as_ref().and_then(|cache| cache.creature(id)){
+            Some(cached)=>{
+                println!("Could not reach dicecloud({}), using the copy of {} downloaded {}. It may be out of date",e,id,describe_age(cached.age));
+                Ok(cached.value)
+            },
+            None=>Err(FetchError::Client(ClientError::Http(e))),
+        },
+        Err(e)=>Err(FetchError::Client(e)),
+    }
+}
+///downloads the portrait and caches it, falling back to the cached copy like `creature`.
+///Without either the portrait is left blank
+pub async fn portrait(client: &DicecloudClient, img_url: &str, offline: bool)->Option<image::DynamicImage>{
+    if img_url.is_empty(){
+        return None;
+    }
+    let cache = Cache::open();
+    let bytes = if offline{
+        cache.as_ref().and_then(|cache| cache.portrait(img_url)).map(|cached| cached.value)
+    } else {
+        match client.image_bytes(img_url).await{
+            Ok(bytes)=>{
+                if let Some(cache)=&cache{
+                    if let Err(e)=cache.store_portrait(img_url, &bytes){
+                        println!("Failed to cache the portrait: {}",e);
+                    }
+                }
+                Some(bytes)
+            },
+            Err(e)=>{
+                let cached = cache.as_ref().and_then(|cache| cache.portrait(img_url));
+                match &cached{
+                    Some(cached)=>println!("Failed to get character portrait({}), using the copy downloaded {}",e,describe_age(cached.age)),
+                    None=>println!("Failed to get character portrait({}), leaving it blank",e),
+                }
+                cached.map(|cached| cached.value)
+            },
+        }
+    };
+    match portrait_from_bytes(&bytes?){
+        Ok(img)=>Some(img),
+        Err(e)=>{
+            println!("Failed to read character portrait({}), leaving it blank",e);
+            None
+        }
+    }
+}
+///eg `3 hours ago`
+fn describe_age(age: Duration)->String{
+    let secs = age.as_secs();
+    let (n, unit) = if secs<60{
+        return "less than a minute ago".to_string();
+    } else if secs<60*60{
+        (secs/60, "minute")
+    } else if secs<24*60*60{
+        (secs/(60*60), "hour")
+    } else {
+        (secs/(24*60*60), "day")
+    };
+    format!("{} {}{} ago",n,unit,if n==1 {""} else {"s"})
+}
+fn read(path: &Path)->Option<(Vec<u8>,Duration)>{
+    let bytes = fs::read(path).ok()?;
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some((bytes, SystemTime::now().duration_since(modified).unwrap_or_default()))
+}
+fn write(path: &Path, bytes: &[u8])->io::Result<()>{
+    if let Some(dir)=path.parent(){
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, bytes)
+}
+///64 bit FNV-1a, stable between runs and rust versions unlike the std hasher
+fn fnv1a(s: &str)->u64{
+    s.bytes().fold(0xcbf29ce484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use serde_json::json;
+
+    ///an empty cache in its own temporary folder
+    fn temp_cache(name: &str)->Cache{
+        let dir = std::env::temp_dir().join(format!("dicecloud_sheet_printer_{}_{}",name,std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        Cache{dir}
+    }
+    ///a client for a server that refuses connections
+    fn unreachable()->DicecloudClient{
+        DicecloudClient::new("http://127.0.0.1:9").unwrap()
+    }
+    #[test]
+    fn fnv1a_matches_the_reference(){
+        assert_eq!(fnv1a(""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a("a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a("foobar"), 0x85944171f73967e8);
+    }
+    #[test]
+    fn portraits_are_keyed_by_url(){
+        let cache = temp_cache("keys");
+        let path = cache.portrait_path("https://example.com/a.png");
+        assert_eq!(path, cache.portrait_path("https://example.com/a.png"));
+        assert_ne!(path, cache.portrait_path("https://example.com/b.png"));
+        assert_eq!(path.file_name().unwrap().len(), 16);
+    }
+    #[tokio::test]
+    async fn offline_uses_the_cached_copy(){
+        let cache = temp_cache("offline");
+        cache.store_creature("abc", &json!({"creatures":[]})).unwrap();
+        assert!(cache.has_creature("abc"));
+        let dir = cache.dir.clone();
+        let creature = creature_in(Some(cache), &unreachable(), "abc", true).await.unwrap();
+        assert_eq!(creature, json!({"creatures":[]}));
+        let missing = creature_in(Some(Cache{dir: dir.clone()}), &unreachable(), "def", true).await;
+        assert!(matches!(missing, Err(FetchError::NotCached(id)) if id=="def"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+    #[tokio::test]
+    async fn unreachable_server_falls_back_to_the_cache(){
+        let cache = temp_cache("fallback");
+        cache.store_creature("abc", &json!({"creatures":[]})).unwrap();
+        let dir = cache.dir.clone();
+        let creature = creature_in(Some(cache), &unreachable(), "abc", false).await.unwrap();
+        assert_eq!(creature, json!({"creatures":[]}));
+        let missing = creature_in(Some(Cache{dir: dir.clone()}), &unreachable(), "def", false).await;
+        assert!(matches!(missing, Err(FetchError::Client(ClientError::Http(_)))));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
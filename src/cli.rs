@@ -0,0 +1,236 @@
+use clap::{Parser,ValueEnum};
+use dicecloud_sheet_printer::{DEFAULT_BASE_URL,character_id};
+use crate::config::Config;
+use std::fs;
+use std::io::{self,IsTerminal};
+
+///Command line arguments for the sheet printer. Anything not supplied here is prompted for on stdin,
+///unless prompting is disabled with `--no-input` or stdin is not a terminal
+#[derive(Parser,Debug,Default)]
+#[command(version, about)]
+pub struct Args{
+    /// id or url of the character to print, the id is the random looking part of the character url(any dicecloud server).
+    /// Giving more than one prints them all, one file each
+    #[arg(conflicts_with="input")]
+    pub characters: Vec<String>,
+    /// print every character listed in this file, one id or url per line. Empty lines and lines starting with # are skipped
+    #[arg(long, conflicts_with="input")]
+    pub batch_file: Option<String>,
+    /// print all the characters into one pdf, with an overview page listing them, instead of one file each.
+    /// The output is then the path of that file, sheet_outputs/party.pdf by default
+    #[arg(long, conflicts_with="input")]
+    pub combine: bool,
+    /// also write party_summary.pdf next to the sheets: one page with the ac, hp, passive perception, spell save dcs
+    /// and so on of every character, for the game master
+    #[arg(long, conflicts_with="input")]
+    pub dm_summary: bool,
+    /// print from the copies downloaded by earlier runs instead of downloading, without logging in
+    #[arg(long, conflicts_with="input")]
+    pub offline: bool,
+    /// keep running after printing, and print again whenever the character changes on dicecloud
+    #[arg(long, conflicts_with_all=["input","offline","batch_file","combine","dm_summary"])]
+    pub watch: bool,
+    /// how often to check for changes in watch mode
+    #[arg(long, value_name="SECONDS", default_value_t=30, value_parser=clap::value_parser!(u64).range(5..))]
+    pub watch_interval: u64,
+    /// render a creature json previously saved from /api/creature/<id> instead of downloading it. Use - for stdin
+    #[arg(short, long)]
+    pub input: Option<String>,
+    /// path of the output file(the extension is added if missing). Defaults to sheet_outputs/<character name>.
+    /// When printing several characters this is the folder to write them to
+    #[arg(short, long)]
+    pub output: Option<String>,
+    /// what to write
+    #[arg(short, long, value_enum, default_value_t=OutputFormat::Pdf)]
+    pub format: OutputFormat,
+    /// print the character's xp on the sheet(formats other than pdf print it unless --no-xp is given)
+    #[arg(long, conflicts_with="no_xp")]
+    pub xp: bool,
+    /// leave the xp box blank
+    #[arg(long)]
+    pub no_xp: bool,
+    /// dicecloud server to use, for self hosted instances [default: https://www.dicecloud.com]
+    #[arg(long, env="DICECLOUD_URL")]
+    pub base_url: Option<String>,
+    /// config file to read settings from [default: <config dir>/dicecloud_sheet_printer/config.toml]
+    #[arg(long)]
+    pub config: Option<String>,
+    /// dicecloud username to log in with
+    #[arg(short, long, env="DICECLOUD_USERNAME")]
+    pub username: Option<String>,
+    /// dicecloud password to log in with
+    #[arg(long, env="DICECLOUD_PASSWORD", hide_env_values=true, conflicts_with="password_file")]
+    pub password: Option<String>,
+    /// read the dicecloud password from the first line of this file
+    #[arg(long)]
+    pub password_file: Option<String>,
+    /// dicecloud api token to use instead of logging in
+    #[arg(long, env="DICECLOUD_TOKEN", hide_env_values=true, conflicts_with="token_file")]
+    pub token: Option<String>,
+    /// read the dicecloud api token from the first line of this file
+    #[arg(long)]
+    pub token_file: Option<String>,
+    /// save the token from logging in to the config directory, later runs use it when no credentials are given
+    #[arg(long)]
+    pub save_token: bool,
+    /// access the character without logging in(only works for public characters)
+    #[arg(long, conflicts_with_all=["username","token","token_file"])]
+    pub anonymous: bool,
+    /// name of an attack to print, may be repeated. Defaults to every attack that fits on the sheet
+    #[arg(short, long="attack")]
+    pub attacks: Vec<String>,
+    /// add pages with the full description of every spell, so the rulebook can stay closed(pdf only)
+    #[arg(long)]
+    pub spell_descriptions: bool,
+    /// add pages with the full text of every feature and trait, grouped by race, class, background and feat, instead of listing their names(pdf only)
+    #[arg(long)]
+    pub feature_descriptions: bool,
+    /// show when the character becomes encumbered and heavily encumbered under the variant encumbrance rule(pdf only)
+    #[arg(long)]
+    pub variant_encumbrance: bool,
+    /// stop with an error when a creature property can't be read, instead of leaving it off the sheet
+    #[arg(long)]
+    pub strict: bool,
+    /// never prompt on stdin, use defaults or fail instead
+    #[arg(long)]
+    pub no_input: bool,
+}
+///The kinds of output the printer can write
+#[derive(ValueEnum,Clone,Copy,Debug,PartialEq,Eq,Default)]
+pub enum OutputFormat{
+    ///the printable character sheet
+    #[default]
+    Pdf,
+    ///the parsed character as json, see the export module for the layout
+    Json,
+    ///the sheet as markdown, for pasting into chat or wikis
+    Markdown,
+    ///the sheet as plain text with aligned columns
+    Text,
+    ///a self contained html page laid out like the pdf, for phones and tablets
+    Html,
+    ///the character's spells on poker sized cards, nine to a page, for cutting out
+    SpellCards,
+}
+impl OutputFormat{
+    pub fn extension(&self)->&'static str{
+        match self{
+            OutputFormat::Pdf=>".pdf",
+            OutputFormat::Json=>".json",
+            OutputFormat::Markdown=>".md",
+            OutputFormat::Text=>".txt",
+            OutputFormat::Html=>".html",
+            OutputFormat::SpellCards=>".pdf",
+        }
+    }
+    ///the file name used when no output is given, without the extension. Spell cards get their own name so they
+    ///don't overwrite the sheet
+    pub fn default_name(&self, char_name: &str)->String{
+        match self{
+            OutputFormat::SpellCards=>format!("{} spell cards",char_name),
+            _=>char_name.to_string(),
+        }
+    }
+}
+impl Args{
+    ///whether we are allowed to fall back to prompting for missing arguments
+    pub fn interactive(&self)->bool{
+        !self.no_input && self.input.as_deref()!=Some("-") && io::stdin().is_terminal()
+    }
+    ///the ids of the characters to print, from the arguments and then the batch file. Urls are turned into ids
+    pub fn character_ids(&self)->Result<Vec<String>,String>{
+        let mut ids = Vec::new();
+        for arg in &self.characters{
+            ids.push(character_id(arg).map_err(|e| e.to_string())?);
+        }
+        if let Some(path)=&self.batch_file{
+            let contents = fs::read_to_string(path).map_err(|e| format!("Failed to read the batch file {}: {}",path,e))?;
+            for (i, line) in contents.lines().enumerate(){
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#'){
+                    continue;
+                }
+                ids.push(character_id(line).map_err(|e| format!("{} line {}: {}",path,i+1,e))?);
+            }
+        }
+        Ok(ids)
+    }
+    ///the password from whichever source was given, if any
+    pub fn password(&self)->io::Result<Option<String>>{
+        if let Some(path)=&self.password_file{
+            return first_line(path).map(Some);
+        }
+        Ok(self.password.clone())
+    }
+    ///the api token from whichever source was given, if any
+    pub fn token(&self)->io::Result<Option<String>>{
+        if let Some(path)=&self.token_file{
+            return first_line(path).map(Some);
+        }
+        Ok(self.token.clone())
+    }
+    ///the server to talk to, from the arguments, then the config file, then the default
+    pub fn base_url(&self, config: &Config)->String{
+        self.base_url.clone().or_else(|| config.base_url.clone()).unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+    }
+    ///whether the xp should be printed, or None if it was not specified
+    pub fn include_xp(&self)->Option<bool>{
+        if self.xp{
+            Some(true)
+        } else if self.no_xp{
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+///the first line of a file, for secrets kept in files
+fn first_line(path: &str)->io::Result<String>{
+    let contents = fs::read_to_string(path)?;
+    Ok(contents.lines().next().unwrap_or("").trim().to_string())
+}
+///prints a message and reads one line from stdin
+pub fn prompt(msg: &str)->String{
+    println!("{}",msg);
+    let mut ans = String::new();
+    io::stdin().read_line(&mut ans).expect("Failed to read from stdin");
+    ans.trim().to_string()
+}
+///asks a yes or no question on stdin
+pub fn confirm(msg: &str)->bool{
+    prompt(msg).to_lowercase().contains('y')
+}
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use clap::error::ErrorKind;
+
+    fn parse(args: &[&str])->Result<Args,clap::Error>{
+        Args::try_parse_from(std::iter::once("dicecloud_sheet_printer").chain(args.iter().copied()))
+    }
+    #[test]
+    fn input_conflicts_with_downloading(){
+        for flag in [&["tARF8SRLPtQq9cjuw"][..], &["--batch-file","ids.txt"], &["--combine"], &["--offline"], &["--watch"]]{
+            let args: Vec<&str> = ["--input","creature.json"].iter().chain(flag).copied().collect();
+            assert_eq!(parse(&args).unwrap_err().kind(), ErrorKind::ArgumentConflict, "{:?}", flag);
+        }
+    }
+    #[test]
+    fn watch_conflicts_with_batches_and_offline(){
+        for flag in ["--offline","--combine","--dm-summary"]{
+            assert_eq!(parse(&["--watch",flag,"tARF8SRLPtQq9cjuw"]).unwrap_err().kind(), ErrorKind::ArgumentConflict, "{}", flag);
+        }
+        assert!(parse(&["--watch","tARF8SRLPtQq9cjuw"]).is_ok());
+    }
+    #[test]
+    fn exclusive_flags_conflict(){
+        assert_eq!(parse(&["--anonymous","--username","someone"]).unwrap_err().kind(), ErrorKind::ArgumentConflict);
+        assert_eq!(parse(&["--xp","--no-xp"]).unwrap_err().kind(), ErrorKind::ArgumentConflict);
+    }
+    #[test]
+    fn watch_interval_is_at_least_5_seconds(){
+        assert_eq!(parse(&[]).unwrap().watch_interval, 30);
+        assert_eq!(parse(&["--watch-interval","5"]).unwrap().watch_interval, 5);
+        assert_eq!(parse(&["--watch-interval","4"]).unwrap_err().kind(), ErrorKind::ValueValidation);
+    }
+}
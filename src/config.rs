@@ -0,0 +1,143 @@
+use std::path::{Path,PathBuf};
+use std::{fmt,fs,io};
+use std::io::Write;
+
+///Settings read from the config file. Command line arguments and environment variables take precedence
+///over anything set here
+#[derive(Debug,Default,PartialEq)]
+pub struct Config{
+    ///the dicecloud server to talk to, eg `https://dicecloud.example.com`
+    pub base_url: Option<String>,
+}
+impl Config{
+    ///the config file used when none is given: `config.toml` in the printer's folder of the user config directory,
+    ///eg `~/.config/dicecloud_sheet_printer/config.toml` on linux
+    pub fn default_path()->Option<PathBuf>{
+        config_dir().map(|dir| dir.join("config.toml"))
+    }
+    ///reads the config from `path`, or the default path if none is given.
+    ///A missing default config file is not an error, a missing file that was asked for is
+    pub fn load(path: Option<&str>)->Result<Config,ConfigError>{
+        let (path, required) = match path{
+            Some(path)=>(PathBuf::from(path),true),
+            None=>match Config::default_path(){
+                Some(path)=>(path,false),
+                None=>return Ok(Config::default()),
+            },
+        };
+        let txt = match fs::read_to_string(&path){
+            Ok(txt)=>txt,
+            Err(e) if e.kind()==io::ErrorKind::NotFound && !required=>return Ok(Config::default()),
+            Err(e)=>return Err(ConfigError::Io(path,e)),
+        };
+        Config::parse(&txt).map_err(|e| ConfigError::Parse(path,e))
+    }
+    ///reads the config from the text of a config file. Every setting is optional, unknown ones are an error
+    ///so a typo doesn't go unnoticed
+    pub fn parse(txt: &str)->Result<Config,String>{
+        let table = txt.parse::<toml::Table>().map_err(|e| e.to_string())?;
+        let mut config = Config::default();
+        for (key,val) in table{
+            match key.as_str(){
+                "base_url"=>config.base_url = Some(val.as_str().ok_or("base_url must be a string")?.to_string()),
+                _=>return Err(format!("unknown setting `{}`, expected `base_url`",key)),
+            }
+        }
+        Ok(config)
+    }
+}
+///Why the config file could not be read
+#[derive(Debug)]
+pub enum ConfigError{
+    Io(PathBuf,io::Error),
+    Parse(PathBuf,String),
+}
+impl fmt::Display for ConfigError{
+    fn fmt(&self, f: &mut fmt::Formatter)->fmt::Result{
+        match self{
+            ConfigError::Io(path,e)=>write!(f,"Failed to read config file {}: {}",path.display(),e),
+            ConfigError::Parse(path,e)=>write!(f,"Invalid config file {}: {}",path.display(),e),
+        }
+    }
+}
+///the printer's folder in the user config directory
+fn config_dir()->Option<PathBuf>{
+    dirs::config_dir().map(|dir| dir.join("dicecloud_sheet_printer"))
+}
+///where `save_token` keeps the token
+pub fn token_path()->Option<PathBuf>{
+    config_dir().map(|dir| dir.join("token"))
+}
+///the token saved by an earlier run, if there is one
+pub fn saved_token()->Option<String>{
+    let txt = fs::read_to_string(token_path()?).ok()?;
+    let token = txt.lines().next().unwrap_or("").trim();
+    if token.is_empty() {None} else {Some(token.to_string())}
+}
+///saves the token for later runs, readable only by the current user, and returns where it was saved
+pub fn save_token(token: &str)->io::Result<PathBuf>{
+    let path = token_path().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound,"no config directory"))?;
+    write_token(&path, token)?;
+    Ok(path)
+}
+fn write_token(path: &Path, token: &str)->io::Result<()>{
+    if let Some(dir)=path.parent(){
+        fs::create_dir_all(dir)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt,PermissionsExt};
+        options.mode(0o600);
+        //mode only applies to new files, so tighten an existing one too
+        if path.exists(){
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    let mut file = options.open(path)?;
+    writeln!(file,"{}",token)
+}
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn parses_the_base_url(){
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+        assert_eq!(Config::parse("base_url = \"http://localhost:3000\"").unwrap().base_url.as_deref(), Some("http://localhost:3000"));
+    }
+    #[test]
+    fn rejects_unknown_settings(){
+        assert!(Config::parse("base_ur = \"http://localhost:3000\"").unwrap_err().contains("base_ur"));
+        assert!(Config::parse("base_url = 3").is_err());
+        assert!(Config::parse("base_url = ").is_err());
+    }
+    #[test]
+    fn token_is_saved_on_the_first_line(){
+        let dir = std::env::temp_dir().join(format!("dicecloud_sheet_printer_token_{}",std::process::id()));
+        let path = dir.join("token");
+        write_token(&path, "first").unwrap();
+        write_token(&path, "second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+    #[cfg(unix)]
+    #[test]
+    fn existing_token_file_is_made_private(){
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("dicecloud_sheet_printer_private_{}",std::process::id()));
+        let path = dir.join("token");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "old\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        write_token(&path, "new").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
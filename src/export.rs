@@ -0,0 +1,271 @@
+//! Exports a parsed `Character` as json, for tools that want the cleaned up data without the pdf.
+//!
+//! The layout is stable: fields are only ever added, and anything that changes the meaning of an
+//! existing field bumps `format_version`. Lists are sorted the same way the sheet sorts them, so
+//! exporting the same character twice gives the same output. The top level object has
+//! - `format_version`: currently 2, version 1 had `features` as a list of names and the background feature's
+//!   summary as its description
+//! - `name`, `race`, `alignment`, `xp`, `portrait`(an image url, or "")
+//! - `background`: `{name, feature}` where feature is laid out like the ones in `features`
+//! - `classes`: `[{name, level, starting_class}]`, starting class first, and `level`, the total
+//! - `ability_scores`: `[{name, score, modifier}]`
+//! - `proficiency_bonus`, `armor_class`, `initiative`, `speed`, `hit_points`, `passive_perception`
+//! - `saving_throws` and `skills`: `[{name, modifier, proficiency}]` where proficiency is one of
+//!   `"none"`, `"half"`, `"proficient"` or `"expert"`
+//! - `hit_dice`: `[{size, count}]`
+//! - `attacks`: `[{name, bonus: {kind, value}, damage}]` where kind is `"bonus"` or `"dc"`
+//! - `actions`: `[{name, action_type, uses}]` where uses is null for unlimited actions
+//! - `resources`: `[{name, total}]`
+//! - `damage_multipliers`: `[{kind, damage_type}]` where kind is `"immunity"`, `"resistance"` or `"vulnerability"`
+//! - `proficiencies`: `{armor, weapons, languages, tools}`, each a list of names
+//! - `traits`: `{personality, ideals, bonds, flaws}`
+//! - `features`: `[{name, summary, description, source, class, uses}]` where source is one of `"race"`,
+//!   `"class"`, `"background"`, `"feat"` or `"other"`, class is the class name for class features and null
+//!   otherwise, description is the full text(the summary if there is none) and uses is null for unlimited features
+//! - `coins`: `{cp, sp, ep, gp, pp}`
+//! - `equipment`: `[{name, plural_name, quantity, requires_attunement, attuned, equipped, weight, value, container,
+//!   container_id}]` where weight and value are for one of the item in pounds and gold pieces, container is the name
+//!   of the container it is in, or "", and container_id its id, since several containers can share a name
+//! - `containers`: `[{name, id, container, container_id, weight, value, carried, contents_weightless}]`, id is the
+//!   dicecloud property id, container and container_id are the one it is in like for items, and weight and value are
+//!   for the empty container
+//! - `spell_slots`: the number of slots for 1st through 9th level
+//! - `spell_lists`: `[{name, save_dc, attack_bonus, max_prepared, levels: [{level, spells}]}]`, levels
+//!   go from cantrips(0) up and every spell is `{name, level, school, casting_time, range, duration,
+//!   verbal, somatic, material, concentration, ritual, prepared, description, higher_levels, damage, save}`
+//!   where prepared is `"always"`, `"prepared"` or `"not_prepared"`, damage is a list like `["3d6 fire"]`
+//!   and save is the saving throw ability, or ""(as are description and higher_levels when missing)
+use serde_json::{json, Value};
+use crate::holding_structs::*;
+
+/// the version of the layout written by `character_to_json`
+pub const FORMAT_VERSION: i64 = 2;
+
+/// builds the json described in the module docs
+/// #Example
+/// ```
+/// use dicecloud_sheet_printer::export::character_to_json;
+/// use dicecloud_sheet_printer::holding_structs::{Character, Class};
+///
+/// let mut character = Character::default();
+/// character.char_name = "Test".to_string();
+/// character.classes.push(Class::new("Wizard".to_string(), 3));
+/// let json = character_to_json(&character);
+/// assert_eq!(json["name"], "Test");
+/// assert_eq!(json["level"], 3);
+/// assert_eq!(json["classes"][0]["name"], "Wizard");
+/// ```
+pub fn character_to_json(character: &Character)->Value{
+    let mut classes = character.classes.clone();
+    classes.sort();
+    let mut skills = character.skills.clone();
+    skills.sort();
+    let mut attacks = character.attacks.clone();
+    attacks.sort();
+    let mut actions = character.actions.clone();
+    actions.sort();
+    let mut resources = character.resources.clone();
+    resources.sort();
+    let mut damage_mults = character.damage_mults.clone();
+    damage_mults.sort();
+    let mut features = character.features.clone();
+    features.sort();
+    let mut equipment = character.equipment.clone();
+    equipment.sort();
+    let mut containers = character.containers.clone();
+    containers.sort();
+    let mut spell_lists = character.spell_lists.clone();
+    spell_lists.sort();
+    let background = &character.background;
+    let (armor, weapons, languages, tools) = &character.other_profs;
+    let (personality, ideals, bonds, flaws) = &character.traits;
+    let (cp, sp, ep, gp, pp) = character.coins;
+    json!({
+        "format_version": FORMAT_VERSION,
+        "name": character.char_name,
+        "race": character.race,
+        "alignment": character.alignment,
+        "xp": character.xp,
+        "portrait": character.char_img,
+        "background": {
+            "name": background.name(),
+            "feature": feature_to_json(background.background_feature()),
+        },
+        "classes": classes.iter().map(|class| json!({
+            "name": class.name(),
+            "level": class.level(),
+            "starting_class": class.start_class,
+        })).collect::<Vec<_>>(),
+        "level": character.level(),
+        "ability_scores": character.ability_scores.iter().map(|score| json!({
+            "name": score.name(),
+            "score": score.score(),
+            "modifier": score.modifier(),
+        })).collect::<Vec<_>>(),
+        "proficiency_bonus": character.prof_bonus,
+        "armor_class": character.ac,
+        "initiative": character.initiative,
+        "speed": character.speed,
+        "hit_points": character.hit_points,
+        "passive_perception": character.passive_perception(),
+        "saving_throws": character.saving_throws.iter().map(skill_to_json).collect::<Vec<_>>(),
+        "skills": skills.iter().map(skill_to_json).collect::<Vec<_>>(),
+        "hit_dice": character.hit_dice.iter().map(|die| json!({
+            "size": die.size(),
+            "count": die.num(),
+        })).collect::<Vec<_>>(),
+        "attacks": attacks.iter().map(|atk| {
+            let (kind, value) = match atk.bonus(){
+                AtkBonus::Bonus(k)=>("bonus", *k),
+                AtkBonus::DC(k)=>("dc", *k),
+            };
+            json!({
+                "name": atk.name(),
+                "bonus": {"kind": kind, "value": value},
+                "damage": atk.damage(),
+            })
+        }).collect::<Vec<_>>(),
+        "actions": actions.iter().map(|act| json!({
+            "name": act.name(),
+            "action_type": action_type_name(act.typ()),
+            "uses": if act.uses()==-1 {Value::Null} else {json!(act.uses())},
+        })).collect::<Vec<_>>(),
+        "resources": resources.iter().map(|res| json!({
+            "name": res.name(),
+            "total": res.total(),
+        })).collect::<Vec<_>>(),
+        "damage_multipliers": damage_mults.iter().map(|mult| {
+            let (kind, typ) = match mult{
+                DamageMult::Immune(typ)=>("immunity", typ),
+                DamageMult::Resist(typ)=>("resistance", typ),
+                DamageMult::Vuln(typ)=>("vulnerability", typ),
+            };
+            json!({"kind": kind, "damage_type": typ})
+        }).collect::<Vec<_>>(),
+        "proficiencies": {
+            "armor": armor,
+            "weapons": weapons,
+            "languages": languages,
+            "tools": tools,
+        },
+        "traits": {
+            "personality": personality,
+            "ideals": ideals,
+            "bonds": bonds,
+            "flaws": flaws,
+        },
+        "features": features.iter().map(feature_to_json).collect::<Vec<_>>(),
+        "coins": {"cp": cp, "sp": sp, "ep": ep, "gp": gp, "pp": pp},
+        "equipment": equipment.iter().map(|itm| json!({
+            "name": itm.name(),
+            "plural_name": itm.plural_name(),
+            "quantity": itm.quantity(),
+            "requires_attunement": itm.requires_attunement(),
+            "attuned": itm.attuned(),
+            "equipped": itm.equipped(),
+            "weight": itm.weight(),
+            "value": itm.value(),
+            "container": container_name(character, itm.container()),
+            "container_id": itm.container(),
+        })).collect::<Vec<_>>(),
+        "containers": containers.iter().map(|cnt| json!({
+            "name": cnt.name(),
+            "id": cnt.id(),
+            "container": container_name(character, cnt.container()),
+            "container_id": cnt.container(),
+            "weight": cnt.weight(),
+            "value": cnt.value(),
+            "carried": cnt.carried(),
+            "contents_weightless": cnt.contents_weightless(),
+        })).collect::<Vec<_>>(),
+        "spell_slots": character.spell_slots,
+        "spell_lists": spell_lists.iter().map(spell_list_to_json).collect::<Vec<_>>(),
+    })
+}
+///the name of the container with the id, "" for none
+fn container_name<'a>(character: &'a Character, id: &str)->&'a str{
+    character.container(id).map_or("", |cnt| cnt.name())
+}
+fn skill_to_json(skill: &Skill)->Value{
+    let prof = match skill.prof(){
+        Proficiency::None=>"none",
+        Proficiency::Half=>"half",
+        Proficiency::Profficient=>"proficient",
+        Proficiency::Expert=>"expert",
+    };
+    json!({
+        "name": skill.name(),
+        "modifier": skill.modifier(),
+        "proficiency": prof,
+    })
+}
+fn feature_to_json(feat: &Feature)->Value{
+    let (source, class) = match feat.source(){
+        FeatureSource::Race=>("race", Value::Null),
+        FeatureSource::Class(class)=>("class", json!(class)),
+        FeatureSource::Background=>("background", Value::Null),
+        FeatureSource::Feat=>("feat", Value::Null),
+        FeatureSource::Other=>("other", Value::Null),
+    };
+    json!({
+        "name": feat.name(),
+        "summary": feat.summary(),
+        "description": feat.description(),
+        "source": source,
+        "class": class,
+        "uses": if feat.uses()==-1 {Value::Null} else {json!(feat.uses())},
+    })
+}
+fn action_type_name(typ: &ActionType)->String{
+    match typ{
+        ActionType::Free=>"free".to_string(),
+        ActionType::Reaction=>"reaction".to_string(),
+        ActionType::Bonus=>"bonus".to_string(),
+        ActionType::Action=>"action".to_string(),
+        ActionType::Long(time)=>time.clone(),
+    }
+}
+fn spell_list_to_json(list: &SpellList)->Value{
+    let mut levels: Vec<&SpellLevel> = list.levels.values().collect();
+    levels.sort_by_key(|lvl| lvl.lvl());
+    json!({
+        "name": list.name,
+        "save_dc": list.save_dc,
+        "attack_bonus": list.atk_bonus,
+        "max_prepared": list.max_prepared,
+        "levels": levels.into_iter().map(|lvl| {
+            let mut spells = lvl.spells().clone();
+            spells.sort();
+            json!({
+                "level": lvl.lvl(),
+                "spells": spells.iter().map(spell_to_json).collect::<Vec<_>>(),
+            })
+        }).collect::<Vec<_>>(),
+    })
+}
+fn spell_to_json(spell: &Spell)->Value{
+    let (verbal, somatic, concentration, ritual) = spell.vscr();
+    let prepared = match spell.prepd(){
+        SpellPrep::AlwaysPrepared=>"always",
+        SpellPrep::Prepared=>"prepared",
+        SpellPrep::NotPrepared=>"not_prepared",
+    };
+    json!({
+        "name": spell.name(),
+        "level": spell.level(),
+        "school": spell.school(),
+        "casting_time": action_type_name(spell.casting_time()),
+        "range": spell.range(),
+        "duration": spell.duration(),
+        "verbal": verbal,
+        "somatic": somatic,
+        "material": spell.material(),
+        "concentration": concentration,
+        "ritual": ritual,
+        "prepared": prepared,
+        "description": spell.description(),
+        "higher_levels": spell.higher_levels(),
+        "damage": spell.damage(),
+        "save": spell.save(),
+    })
+}
@@ -1,9 +1,14 @@
 use serde_json::Value;
+use crate::properties::{Property,PropertyKind,Calculation,InlineText};
+use crate::bns_translator;
 use std::cmp::{PartialOrd,Ordering,Ord};
-use std::collections::HashMap;
+use std::collections::{HashMap,HashSet};
+use std::sync::LazyLock;
 use genpdf::{RenderResult,Element,Context,render::Area,style::Style,error::Error,Mm,Size};
 use owned_chars::OwnedChars;
 use std::fmt::{self,Write};
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 ///defines an ability score by the value(score) and name
 #[derive(Clone,Eq,PartialEq,Hash,Debug,Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -69,6 +74,7 @@ impl Skill{
         Skill {bonus, name, prof_rank}
     }
 }
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl PartialOrd for Skill{
     fn partial_cmp(&self, other: &Skill)->Option<Ordering>{
         if self.name()!=other.name(){
@@ -104,6 +110,7 @@ impl Class{
         Class { name, level, start_class: false}
     }
 }
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl PartialOrd for Class{
     fn partial_cmp(&self, other: &Class) -> Option<Ordering> {
         if self.start_class != other.start_class {
@@ -120,21 +127,87 @@ impl Ord for Class{
         self.partial_cmp(other).unwrap()
     }
 }
+///where a feature comes from, features are grouped by it on the feature pages.
+///It is the nearest class, or property tagged `race`, `subrace`, `background` or `feat`, the feature is inside of
+/// #Example
+/// ```
+/// use dicecloud_sheet_printer::holding_structs::{Character, FeatureSource};
+/// use serde_json::json;
+///
+/// let char_json = json!({"creatures":[{"name":"Test","denormalizedStats":{"xp":0}}],
+///     "creatureProperties":[{"_id":"cls","type":"class","order":0,"tags":[],"name":"Wizard","level":2},
+///     {"_id":"ftr","type":"feature","order":1,"tags":[],"name":"Arcane Recovery","ancestors":[{"id":"cls"}],
+///         "summary":{"value":"Recover slots"},"uses":{"value":1}}]});
+/// let character = Character::try_from_json(char_json, json!({})).unwrap();
+/// assert_eq!(character.features[0].source(), &FeatureSource::Class("Wizard".to_string()));
+/// assert_eq!(character.features[0].to_string(), "Arcane Recovery(_/1)");
+/// ```
 #[derive(Debug, Eq, PartialEq,Clone,Hash,Default,PartialOrd,Ord)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FeatureSource{
+    Race,
+    ///a class feature, with the name of the class
+    Class(String),
+    Background,
+    Feat,
+    ///anything not inside a race, class, background or feat
+    #[default]
+    Other,
+}
+impl fmt::Display for FeatureSource{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self{
+            FeatureSource::Race=>write!(f,"Race"),
+            FeatureSource::Class(class)=>write!(f,"{}",class),
+            FeatureSource::Background=>write!(f,"Background"),
+            FeatureSource::Feat=>write!(f,"Feats"),
+            FeatureSource::Other=>write!(f,"Other"),
+        }
+    }
+}
+///a feature or trait, with its one line summary and its full description
+#[derive(Debug, Eq, PartialEq,Clone,Hash,PartialOrd,Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Feature{
     name: String,
+    summary: String,
     description: String,
+    source: FeatureSource,
+    uses: i64 //-1=infty
 }
 impl Feature{
     pub fn name(&self)->&String{
         &self.name
     }
+    pub fn summary(&self)->&String{
+        &self.summary
+    }
+    ///the full text of the feature, or the summary if it has no description
     pub fn description(&self)->&String{
-        &self.description
+        if self.description.is_empty() {&self.summary} else {&self.description}
+    }
+    pub fn source(&self)->&FeatureSource{
+        &self.source
+    }
+    pub fn uses(&self)->i64{
+        self.uses
     }
-    pub fn new(name: String, description: String)->Feature{
-        Feature { name, description}
+    pub fn new(name: String, summary: String, description: String, source: FeatureSource, uses: i64)->Feature{
+        Feature { name, summary, description, source, uses}
+    }
+}
+impl Default for Feature{
+    fn default()->Feature{
+        Feature::new(String::new(),String::new(),String::new(),FeatureSource::Other,-1)
+    }
+}
+impl fmt::Display for Feature{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.uses==-1{
+            return write!(f,"{}",self.name);
+        }
+        let blank = "_".repeat(format!("{}",self.uses).len());
+        write!(f,"{}({}/{})",self.name,blank,self.uses)
     }
 }
 ///a background is a name and a description
@@ -256,7 +329,7 @@ impl Attack{
         }
     }
 }
-///an item has a quantity and a name
+///an item has a quantity and a name, what one of them weighs and is worth, and the container it is in
 #[derive(Debug, Eq, PartialEq,Clone,Hash,Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Item{
@@ -264,6 +337,16 @@ pub struct Item{
     name: String,
     plural_name: String,
     requires_attunement: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    attuned: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    equipped: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    weight: i64,//hundredths of a pound
+    #[cfg_attr(feature = "serde", serde(default))]
+    value: i64,//copper pieces
+    #[cfg_attr(feature = "serde", serde(default))]
+    container: String,//id of the container, "" when it isn't in one
 }
 impl Item{
     pub fn quantity(&self)->i64{
@@ -278,19 +361,139 @@ impl Item{
     pub fn requires_attunement(&self)->bool{
         self.requires_attunement
     }
+    pub fn attuned(&self)->bool{
+        self.attuned
+    }
+    pub fn equipped(&self)->bool{
+        self.equipped
+    }
+    ///the weight of one of the item in pounds
+    pub fn weight(&self)->f64{
+        self.weight as f64/100.0
+    }
+    ///the value of one of the item in gold pieces
+    pub fn value(&self)->f64{
+        self.value as f64/100.0
+    }
+    ///the weight of all of them in pounds
+    pub fn total_weight(&self)->f64{
+        (self.weight*self.quantity) as f64/100.0
+    }
+    ///the value of all of them in gold pieces
+    pub fn total_value(&self)->f64{
+        (self.value*self.quantity) as f64/100.0
+    }
+    ///the id of the container the item is in, or "" if it is carried loose. `Character::container` finds it
+    pub fn container(&self)->&String{
+        &self.container
+    }
     pub fn new(quantity: i64,name: String,plural_name: String)->Item{
-        Item { quantity, name,plural_name,requires_attunement: false}
+        Item { quantity, name,plural_name,..Default::default()}
     }
     pub fn needs_attuned(&mut self){
         self.requires_attunement=true;
     }
+    pub fn attune(&mut self){
+        self.attuned=true;
+    }
+    pub fn equip(&mut self){
+        self.equipped=true;
+    }
+    ///sets the weight of one of the item, in pounds. It is kept to a hundredth of a pound
+    pub fn set_weight(&mut self, pounds: f64){
+        self.weight=(pounds*100.0).round() as i64;
+    }
+    ///sets the value of one of the item, in gold pieces. It is kept to the copper piece
+    pub fn set_value(&mut self, gold: f64){
+        self.value=(gold*100.0).round() as i64;
+    }
+    pub fn set_container(&mut self, container: String){
+        self.container=container;
+    }
+}
+///a container like a backpack or a bag of holding, items and other containers can be in it
+/// #Example
+/// ```
+/// use dicecloud_sheet_printer::holding_structs::Character;
+/// use serde_json::json;
+///
+/// let char_json = json!({"creatures":[{"name":"Test","denormalizedStats":{"xp":0}}],
+///     "creatureProperties":[{"_id":"bag","type":"container","order":0,"tags":[],"name":"Backpack","weight":5,"carried":true},
+///     {"_id":"rope","type":"item","order":1,"tags":[],"name":"Rope","quantity":2,"weight":10,"value":1,
+///         "ancestors":[{"id":"bag"}]},
+///     {"_id":"chest","type":"container","order":2,"tags":[],"name":"Chest","weight":25,"carried":false}]});
+/// let character = Character::try_from_json(char_json, json!({})).unwrap();
+/// let backpack = character.container(character.equipment[0].container()).unwrap();
+/// assert_eq!(backpack.name(), "Backpack");
+/// assert_eq!(character.contents_weight(backpack.id()), 20.0);
+/// assert_eq!(character.contents_value(backpack.id()), 2.0);
+/// //the chest is left at home
+/// assert_eq!(character.contents_weight(""), 25.0);
+/// assert_eq!(character.carried_weight(), 25.0);
+/// ```
+#[derive(Debug, Eq, PartialEq,Clone,Hash,Default,PartialOrd,Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Container{
+    name: String,
+    id: String,//of the dicecloud property, containers are told apart by it since names can repeat
+    container: String,//id of the container it is in, "" when it isn't in one
+    weight: i64,//hundredths of a pound
+    value: i64,//copper pieces
+    carried: bool,
+    contents_weightless: bool,
+}
+impl Container{
+    pub fn name(&self)->&String{
+        &self.name
+    }
+    ///the id of the dicecloud property, which items and containers inside it refer to
+    pub fn id(&self)->&String{
+        &self.id
+    }
+    ///the id of the container this one is in, or "" if it is carried loose
+    pub fn container(&self)->&String{
+        &self.container
+    }
+    ///the weight of the empty container in pounds
+    pub fn weight(&self)->f64{
+        self.weight as f64/100.0
+    }
+    ///the value of the empty container in gold pieces
+    pub fn value(&self)->f64{
+        self.value as f64/100.0
+    }
+    ///whether the character has it on them, rather than stored somewhere
+    pub fn carried(&self)->bool{
+        self.carried
+    }
+    ///whether what is inside doesn't add to the weight carried, like a bag of holding
+    pub fn contents_weightless(&self)->bool{
+        self.contents_weightless
+    }
+    ///weight and value in pounds and gold pieces, like `Item::set_weight` and `Item::set_value`
+    pub fn new(id: String, name: String, weight: f64, value: f64, carried: bool, contents_weightless: bool)->Container{
+        Container{name, id, container: String::new(), weight: (weight*100.0).round() as i64, value: (value*100.0).round() as i64,
+            carried, contents_weightless}
+    }
+    pub fn set_container(&mut self, container: String){
+        self.container=container;
+    }
 }
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl PartialOrd for Item{
     fn partial_cmp(&self,other: &Item)->Option<Ordering>{
         if &self.name != other.name(){
             return self.name.partial_cmp(other.name());
         }
-        self.quantity.partial_cmp(&other.quantity())
+        //the rest only break ties, so items equal by `==` are the only ones that compare equal
+        Some(self.quantity.cmp(&other.quantity())
+            .then_with(|| self.container.cmp(&other.container))
+            .then_with(|| self.plural_name.cmp(&other.plural_name))
+            .then_with(|| self.requires_attunement.cmp(&other.requires_attunement))
+            .then_with(|| self.attuned.cmp(&other.attuned))
+            .then_with(|| self.equipped.cmp(&other.equipped))
+            .then_with(|| self.weight.cmp(&other.weight))
+            .then_with(|| self.value.cmp(&other.value)))
     }
 }
 impl Ord for Item{
@@ -313,6 +516,8 @@ pub enum SpellPrep{
     #[default]
     NotPrepared,
 }
+///the heading dicecloud's srd spells put before the at higher levels text, in bold or italics
+static AT_HIGHER_LEVELS: LazyLock<regex::Regex> = LazyLock::new(|| regex::Regex::new(r"(?i)[*_]*\s*at higher levels\s*[.:]?\s*[*_]*\s*[.:]?").unwrap());
 #[derive(Debug, Eq, PartialEq,Clone,Hash,Default,PartialOrd,Ord)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Spell{
@@ -324,7 +529,15 @@ pub struct Spell{
     range: String,
     vscr: (bool,bool,bool,bool),
     material: String,
-    prepd: SpellPrep
+    prepd: SpellPrep,
+    #[cfg_attr(feature = "serde", serde(default))]
+    description: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    higher_levels: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    damage: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    save: String,
 }
 impl Spell{
     pub fn name(&self)->&String{
@@ -361,8 +574,49 @@ impl Spell{
     pub fn prepd(&self)->SpellPrep{
         self.prepd
     }
+    ///the full text of the spell, without the at higher levels part
+    pub fn description(&self)->&String{
+        &self.description
+    }
+    ///what changes when cast with a higher level slot, empty if nothing does
+    pub fn higher_levels(&self)->&String{
+        &self.higher_levels
+    }
+    ///the damage and healing the spell does, like `3d6 fire`
+    pub fn damage(&self)->&Vec<String>{
+        &self.damage
+    }
+    ///the ability of the saving throw the spell asks for, like `Dexterity`, empty if there is none
+    pub fn save(&self)->&String{
+        &self.save
+    }
+    ///sets the description, splitting off the at higher levels paragraph if it has one
+    /// #Example
+    /// ```
+    /// use dicecloud_sheet_printer::holding_structs::Spell;
+    ///
+    /// let mut spell = Spell::default();
+    /// spell.set_description("Deals 3d6 fire damage.\n\n**At Higher Levels.** +1d6 per slot level above 1st.");
+    /// assert_eq!(spell.description(), "Deals 3d6 fire damage.");
+    /// assert_eq!(spell.higher_levels(), "+1d6 per slot level above 1st.");
+    /// ```
+    pub fn set_description(&mut self, description: &str){
+        let (description, higher) = match AT_HIGHER_LEVELS.find(description){
+            Some(m)=>(&description[..m.start()], &description[m.end()..]),
+            None=>(description, ""),
+        };
+        self.description = description.trim().to_string();
+        self.higher_levels = higher.trim().to_string();
+    }
+    pub fn add_damage(&mut self, damage: String){
+        self.damage.push(damage);
+    }
+    pub fn set_save(&mut self, save: String){
+        self.save = save;
+    }
+    #[allow(clippy::too_many_arguments)]
     pub fn new(name: String, level: i64, casting_time: ActionType, duration: String, school: String, range: String, vscr: (bool,bool,bool,bool),material: String)->Spell{
-        Spell{name,level,casting_time,duration,school,range,vscr,material,prepd:SpellPrep::NotPrepared}
+        Spell{name,level,casting_time,duration,school,range,vscr,material,prepd:SpellPrep::NotPrepared,..Default::default()}
     }
     pub fn prepare(&mut self){
         self.prepd = SpellPrep::Prepared;
@@ -413,6 +667,7 @@ impl SpellList{
         self.levels.iter().fold(0,|mx,val| if val.1.lvl()>mx {val.1.lvl()} else {mx})
     }
 }
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl PartialOrd for SpellList{
     fn partial_cmp(&self,other: &SpellList)->Option<Ordering>{
         let own_lvl = self.max_lvl();
@@ -543,6 +798,22 @@ impl fmt::Display for Resource{
     }
 }
 ///a struct for parsing the character into
+///
+///With the `serde` feature every holding struct can be serialized and deserialized
+/// #Example
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use dicecloud_sheet_printer::holding_structs::{Character, Class};
+///
+/// let mut character = Character::default();
+/// character.char_name = "Test".to_string();
+/// character.classes.push(Class::new("Wizard".to_string(), 3));
+/// let json = serde_json::to_string(&character).unwrap();
+/// let back: Character = serde_json::from_str(&json).unwrap();
+/// assert_eq!(back, character);
+/// # }
+/// ```
 #[derive(Debug, Eq, PartialEq,Clone,Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Character{
@@ -567,72 +838,337 @@ pub struct Character{
     pub actions: Vec<Action>,
     pub resources: Vec<Resource>,
     pub equipment: Vec<Item>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub containers: Vec<Container>,
     pub traits: (String, String, String, String),//Personality, Ideals, Bonds, Flaws
-    pub features: Vec<String>,
+    pub features: Vec<Feature>,
     pub other_profs: (Vec<String>,Vec<String>,Vec<String>,Vec<String>),//armor,weapon,language, tool
     pub coins: (i64,i64,i64,i64,i64),//cp,sp,ep,gp,pp
+    ///coins kept in a container, as the container id and how many, the rest of `coins` are carried loose
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub container_coins: Vec<(String,i64)>,
     pub spell_lists: Vec<SpellList>,
     pub spell_slots: [i64;9],//1st,2nd,...9th
     pub char_img: String,
 }
 
 impl Character{
+    /// 10 plus the perception modifier and any bonus to passive perception
+    pub fn passive_perception(&self)->i64{
+        10+self.skills.iter().filter(|skill| skill.name()=="Perception")
+            .map(|skill| skill.modifier()+self.passive_bonus).sum::<i64>()
+    }
+    /// the container with the id, as given by `Item::container` and `Container::container`
+    pub fn container(&self, id: &str)->Option<&Container>{
+        self.containers.iter().find(|cnt| cnt.id()==id)
+    }
+    /// how many coins are in the container with the id, or not in any container for ""
+    pub fn coins_in(&self, container: &str)->i64{
+        let stored = self.container_coins.iter().filter(|(id, _)| container.is_empty() || id==container).map(|(_, count)| count).sum::<i64>();
+        if container.is_empty(){
+            let (cp, sp, ep, gp, pp) = self.coins;
+            cp+sp+ep+gp+pp-stored
+        } else {
+            stored
+        }
+    }
+    /// the weight in pounds of everything in the container with the id, including the containers inside it and what
+    /// they hold and coins at 50 to the pound, or of everything not in a container for "". What is inside a container with weightless contents is
+    /// left out, and so are containers that aren't carried, like `carried_weight`
+    /// #Example
+    /// ```
+    /// use dicecloud_sheet_printer::holding_structs::Character;
+    /// use serde_json::json;
+    ///
+    /// //containers are found by id, so sharing a name is fine
+    /// let char_json = json!({"creatures":[{"name":"Test","denormalizedStats":{"xp":0}}],
+    ///     "creatureProperties":[{"_id":"a","type":"container","order":0,"tags":[],"name":"Pouch","weight":1,"carried":true},
+    ///         {"_id":"b","type":"container","order":1,"tags":[],"name":"Pouch","weight":1,"carried":true},
+    ///         {"_id":"c","type":"container","order":2,"tags":[],"name":"Pouch 2","weight":1,"carried":true,"ancestors":[{"id":"b"}]},
+    ///         {"_id":"gem","type":"item","order":3,"tags":[],"name":"Gem","quantity":1,"weight":10,"ancestors":[{"id":"b"},{"id":"c"}]}]});
+    /// let character = Character::try_from_json(char_json, json!({})).unwrap();
+    /// assert_eq!(character.contents_weight("c"), 10.0);
+    /// assert_eq!(character.contents_weight("b"), 11.0);
+    /// assert_eq!(character.contents_weight(""), 13.0);
+    ///
+    /// //coins weigh nothing while they are in a chest that is left behind
+    /// let char_json = json!({"creatures":[{"name":"Test","denormalizedStats":{"xp":0}}],
+    ///     "creatureProperties":[{"_id":"chest","type":"container","order":0,"tags":[],"name":"Chest","weight":25,"carried":false},
+    ///         {"_id":"gp","type":"item","order":1,"tags":[],"name":"Gold Pieces","quantity":500,"ancestors":[{"id":"chest"}]},
+    ///         {"_id":"sp","type":"item","order":2,"tags":[],"name":"Silver Pieces","quantity":50}]});
+    /// let character = Character::try_from_json(char_json, json!({})).unwrap();
+    /// assert_eq!(character.coins, (0, 50, 0, 500, 0));
+    /// assert_eq!(character.contents_weight("chest"), 10.0);
+    /// assert_eq!(character.carried_weight(), 1.0);
+    /// ```
+    pub fn contents_weight(&self, container: &str)->f64{
+        self.contents_total(container, 0, &|itm| itm.total_weight(), &|coins| coins as f64/50.0,
+            &|cnt, inside| if !cnt.carried() {0.0} else if cnt.contents_weightless() {cnt.weight()} else {cnt.weight()+inside})
+    }
+    /// the value in gold pieces of everything in the container, like `contents_weight` but counting every container
+    pub fn contents_value(&self, container: &str)->f64{
+        self.contents_total(container, 0, &|itm| itm.total_value(), &|_| 0.0, &|cnt, inside| cnt.value()+inside)
+    }
+    /// adds up `item` for what is in the container, `coins` for the number of coins in it and `nested` for each container
+    /// inside it given the total of what that one holds. Containers nested deeper than there are containers can only come from a loop in the json,
+    /// those are left out
+    fn contents_total(&self, container: &str, depth: usize, item: &dyn Fn(&Item)->f64, coins: &dyn Fn(i64)->f64,
+        nested: &dyn Fn(&Container,f64)->f64)->f64{
+        if depth>self.containers.len(){
+            return 0.0;
+        }
+        let items: f64 = self.equipment.iter().filter(|itm| itm.container()==container).map(item).sum();
+        let containers: f64 = self.containers.iter().filter(|cnt| cnt.container()==container)
+            .map(|cnt| nested(cnt, self.contents_total(cnt.id(), depth+1, item, coins, nested))).sum();
+        items+coins(self.coins_in(container))+containers
+    }
+    /// the weight in pounds the character carries: the items not in a container, the containers they carry with what
+    /// is in them(unless it is weightless) and their coins, at 50 to the pound
+    pub fn carried_weight(&self)->f64{
+        self.contents_weight("")
+    }
+    /// how much the character carries and can carry, from their strength score. None without a strength score
+    /// #Example
+    /// ```
+    /// use dicecloud_sheet_printer::holding_structs::{AbilityScore, Character, Item};
+    ///
+    /// let mut character = Character::default();
+    /// character.ability_scores.push(AbilityScore::new("Strength".to_string(), 10));
+    /// let mut rope = Item::new(1, "Rope".to_string(), "Ropes".to_string());
+    /// rope.set_weight(10.0);
+    /// character.equipment.push(rope);
+    /// character.coins.3 = 100;
+    /// let encumbrance = character.encumbrance().unwrap();
+    /// assert_eq!(encumbrance.carried, 12.0);
+    /// assert_eq!(encumbrance.capacity, 150.0);
+    /// assert_eq!(encumbrance.status(true), "unencumbered");
+    /// ```
+    pub fn encumbrance(&self)->Option<Encumbrance>{
+        let strength = self.ability_scores.iter().find(|score| score.name()=="Strength")?.score() as f64;
+        Some(Encumbrance{
+            carried: self.carried_weight(),
+            capacity: strength*15.0,
+            encumbered: strength*5.0,
+            heavily_encumbered: strength*10.0,
+        })
+    }
+    /// the character level, the sum of the class levels
+    pub fn level(&self)->i64{
+        self.classes.iter().map(|class| class.level()).sum()
+    }
     /// #Panics
-    /// when properties do not follow the expected structure, (ie a core stat can't be found, or a property does not have an expected entry), the function will panic
-    pub fn new(mut char_json: Value,race_decoder: Value)->Character{
-        let char_name=&char_json["creatures"][0]["name"];
-        if char_name == &Value::Null{
-            panic!("cannot find char name, probably because the api is wrong");
-        }
-        let char_name = char_name.as_str().unwrap().to_string();
-        let alignment=char_json["creatures"][0]["alignment"].as_str().unwrap_or("").to_string();
-        let xp: i64=char_json["creatures"][0]["denormalizedStats"]["xp"].as_i64().unwrap();
-        let mut ability_scores: Vec<AbilityScore> = vec![];
-        let mut skills: Vec<Skill> = vec![];
-        let mut saving_throws: Vec<Skill> = vec![];
-        let mut initiative: i64=0;
-        let mut prof_bonus: i64=0;
-        let mut damage_mults: Vec<DamageMult> = vec![];
-        let mut passive_bonus: i64 =0;
-        let mut speed: i64=0;
-        let mut hit_points: i64=0;
-        let mut ac: i64=0;
-        let mut traits = (String::new(),String::new(),String::new(),String::new());
-        let mut attacks_dict: HashMap<String,Attack> =HashMap::new();
-        let mut attacks: Vec<Attack>=vec![];
-        let mut actions: Vec<Action>=vec![];
-        let mut classes: Vec<Class> = vec![];
-        let mut features: Vec<String> = vec![];
-        let mut starting_class = String::new();
-        let mut resources: Vec<Resource> = vec![];
-        let mut equipment: Vec<Item> = vec![];
-        let mut hit_dice: Vec<Die> = vec![];
-        let mut background: Background=Background::default();
-        let mut race: String = String::new();
-        let mut coins = (0,0,0,0,0);
-        let mut other_profs: (Vec<String>,Vec<String>,Vec<String>,Vec<String>) = (vec![],vec![],vec![],vec![]);
-        let mut char_img = String::new();
-        let mut spell_ls_dict: HashMap<String,SpellList> =HashMap::new();
-        let mut spell_slots: [i64;9] = [0;9];
-        if let Some(url) =char_json["creatures"][0]["avatarPicture"].as_str(){
-            char_img = url.to_string();
-        } else if let Some(url) = char_json["creatures"][0]["picture"].as_str(){
-            char_img = url.to_string();
-        }
-        let props = char_json["creatureProperties"].as_array_mut().unwrap();
-        props.sort_by(|a,b| a["order"].as_i64().unwrap().cmp(&b["order"].as_i64().unwrap()));
-        for val in props{
-            if val["removed"].as_bool()==Some(true){
-                continue;
+    /// when properties do not follow the expected structure, (ie a core stat can't be found, or a property does not have an expected entry), the function will panic.
+    /// Use `try_from_json` to get the error instead
+    pub fn new(char_json: Value,race_decoder: Value)->Character{
+        match Character::try_from_json(char_json, race_decoder){
+            Ok(character)=>character,
+            Err(e)=>panic!("{}",e),
+        }
+    }
+    /// parses a character from the json returned by the creature endpoint
+    /// #Errors
+    /// returns the first property that does not follow the expected structure
+    /// #Example
+    /// ```
+    /// use dicecloud_sheet_printer::holding_structs::{Character, CharacterError};
+    /// use serde_json::json;
+    ///
+    /// let char_json = json!({"creatures":[{"name":"Test","denormalizedStats":{"xp":0}}],
+    ///     "creatureProperties":[{"_id":"abc","type":"class","order":0,"tags":[],"name":"Wizard"}]});
+    /// let err = Character::try_from_json(char_json, json!({})).unwrap_err();
+    /// assert_eq!(err, CharacterError::InvalidProperty{id: "abc".to_string(), typ: "class".to_string(), field: "level".to_string(), mismatch: None});
+    ///
+    /// //a field with the wrong type says what it should have been
+    /// let char_json = json!({"creatures":[{"name":"Test","denormalizedStats":{"xp":0}}],
+    ///     "creatureProperties":[{"_id":"abc","type":"class","order":0,"tags":[],"name":"Wizard","level":"3"}]});
+    /// let err = Character::try_from_json(char_json, json!({})).unwrap_err();
+    /// assert_eq!(err.to_string(), "class property abc has an invalid level: invalid type: string \"3\", expected i64");
+    /// ```
+    pub fn try_from_json(char_json: Value,race_decoder: Value)->Result<Character,CharacterError>{
+        Character::parse(char_json, race_decoder, false).map(|(character,_)| character)
+    }
+    /// parses a character like `try_from_json`, but skips any property that can't be read instead of failing.
+    /// The skipped properties are returned alongside the character
+    /// #Errors
+    /// fails only when the creature itself can't be read
+    /// #Example
+    /// ```
+    /// use dicecloud_sheet_printer::holding_structs::{Character, CharacterError};
+    /// use serde_json::json;
+    ///
+    /// let char_json = json!({"creatures":[{"name":"Test","denormalizedStats":{"xp":0}}],
+    ///     "creatureProperties":[{"_id":"abc","type":"class","order":0,"tags":[],"name":"Wizard"},
+    ///     {"_id":"def","type":"class","order":1,"tags":[],"name":"Rogue","level":2}]});
+    /// let (character, warnings) = Character::try_from_json_lenient(char_json, json!({})).unwrap();
+    /// assert_eq!(character.classes.len(), 1);
+    /// assert_eq!(warnings, vec![CharacterError::InvalidProperty{id: "abc".to_string(), typ: "class".to_string(), field: "level".to_string(), mismatch: None}]);
+    /// ```
+    pub fn try_from_json_lenient(char_json: Value,race_decoder: Value)->Result<(Character,Vec<CharacterError>),CharacterError>{
+        Character::parse(char_json, race_decoder, true)
+    }
+    fn parse(mut char_json: Value,race_decoder: Value,lenient: bool)->Result<(Character,Vec<CharacterError>),CharacterError>{
+        let creature = &char_json["creatures"][0];
+        let char_name = match creature["name"].as_str(){
+            Some(name)=>name.to_string(),
+            None if creature.is_null()=>return Err(CharacterError::NoCreature),
+            None=>return Err(CharacterError::InvalidCreature{field: "name".to_string()}),
+        };
+        let mut builder = CharacterBuilder::default();
+        builder.character.char_name = char_name;
+        builder.character.alignment=creature["alignment"].as_str().unwrap_or("").to_string();
+        builder.character.xp=creature["denormalizedStats"]["xp"].as_i64()
+            .ok_or_else(|| CharacterError::InvalidCreature{field: "denormalizedStats.xp".to_string()})?;
+        if let Some(url) =creature["avatarPicture"].as_str(){
+            builder.character.char_img = url.to_string();
+        } else if let Some(url) = creature["picture"].as_str(){
+            builder.character.char_img = url.to_string();
+        }
+        let props = char_json["creatureProperties"].as_array_mut()
+            .ok_or_else(|| CharacterError::InvalidCreature{field: "creatureProperties".to_string()})?;
+        props.sort_by_key(|val| val["order"].as_i64().unwrap_or(i64::MAX));
+        let mut warnings: Vec<CharacterError> = vec![];
+        for val in props.iter(){
+            let added = read_property(val).and_then(|prop| builder.add_property(&prop)
+                .map_err(|field| CharacterError::property(val, field, None)));
+            match added{
+                Ok(())=>{},
+                Err(e) if lenient=>warnings.push(e),
+                Err(e)=>return Err(e),
             }
-            if val["type"].as_str()==Some("attribute") && val["attributeType"].as_str()==Some("ability"){
-                ability_scores.push(AbilityScore::new(val["name"].as_str().unwrap().to_string(),
-                    val["total"].as_i64().unwrap()));
-            } else if val["type"].as_str()==Some("skill"){
-                if val["name"].as_str()==Some("Initiative"){
-                    initiative=val["value"].as_i64().unwrap();
-                } else if val["skillType"].as_str()==Some("save"){
-                    let prf=val["proficiency"].as_f64();
+        }
+        Ok((builder.build(race_decoder),warnings))
+    }
+}
+///how much a character carries and can carry, in pounds, for a medium creature. The encumbered thresholds are for
+///the variant encumbrance rule, past them speed drops by 10 and 20 feet
+#[derive(Debug,Clone,Copy,PartialEq,Default)]
+pub struct Encumbrance{
+    pub carried: f64,
+    ///15 times strength
+    pub capacity: f64,
+    ///5 times strength
+    pub encumbered: f64,
+    ///10 times strength
+    pub heavily_encumbered: f64,
+}
+impl Encumbrance{
+    ///`over capacity` past the carrying capacity, otherwise `unencumbered`. With the variant rule `encumbered` or
+    ///`heavily encumbered` past those thresholds
+    pub fn status(&self, variant: bool)->&'static str{
+        if self.carried>self.capacity{
+            "over capacity"
+        } else if variant && self.carried>self.heavily_encumbered{
+            "heavily encumbered"
+        } else if variant && self.carried>self.encumbered{
+            "encumbered"
+        } else {
+            "unencumbered"
+        }
+    }
+}
+///the ways parsing a character can fail
+#[derive(Debug,Clone,PartialEq,Eq,Hash)]
+pub enum CharacterError{
+    ///there is no creature in the json, usually because the api returned an error instead
+    NoCreature,
+    ///a field of the creature itself is missing or has the wrong type
+    InvalidCreature{field: String},
+    ///a creature property is missing a field we need, or it has the wrong type. `field` is the path to the field,
+    ///like `attackRoll.value`, and `mismatch` says what was wrong with its type, None when it is missing or holds
+    ///a value the printer can't use
+    InvalidProperty{id: String, typ: String, field: String, mismatch: Option<String>},
+}
+impl CharacterError{
+    /// the error for `field` of the property `val`
+    fn property(val: &Value, field: &str, mismatch: Option<String>)->CharacterError{
+        CharacterError::InvalidProperty{
+            id: val["_id"].as_str().unwrap_or("unknown").to_string(),
+            typ: val["type"].as_str().unwrap_or("unknown").to_string(),
+            field: field.to_string(),
+            mismatch,
+        }
+    }
+}
+impl fmt::Display for CharacterError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self{
+            CharacterError::NoCreature => write!(f,"cannot find the creature, probably because the api is wrong"),
+            CharacterError::InvalidCreature{field} => write!(f,"the creature's {} is missing or invalid",field),
+            CharacterError::InvalidProperty{id,typ,field,mismatch:None} => write!(f,"{} property {} has a missing or invalid {}",typ,id,field),
+            CharacterError::InvalidProperty{id,typ,field,mismatch:Some(mismatch)} => write!(f,"{} property {} has an invalid {}: {}",typ,id,field,mismatch),
+        }
+    }
+}
+impl std::error::Error for CharacterError{}
+///reads a property, naming the field that is missing or has the wrong type if it fails
+fn read_property(val: &Value)->Result<Property,CharacterError>{
+    Property::from_json(val).map_err(|err|{
+        let path = err.path().to_string();
+        let message = err.inner().to_string();
+        //serde reports a missing field on the struct holding it, so add the field to the path
+        match message.strip_prefix("missing field `").and_then(|rest| rest.strip_suffix('`')){
+            Some(field) if path=="." => CharacterError::property(val, field, None),
+            Some(field) => CharacterError::property(val, &format!("{}.{}",path,field), None),
+            None => CharacterError::property(val, &path, Some(message)),
+        }
+    })
+}
+///turns a missing field into the name of that field, for use with `?` in `CharacterBuilder`
+fn required<T>(opt: Option<T>, field: &'static str)->Result<T,&'static str>{
+    opt.ok_or(field)
+}
+///which of copper, silver, electrum, gold and platinum an item is, if it is coins: named like "Gold Pieces", "Gold Coin"
+///or "gp", or tagged as coins or currency with the metal in its name
+fn coin_kind(prop: &Property, name: &str)->Option<usize>{
+    const METALS: [(&str,&str);5] = [("copper","cp"),("silver","sp"),("electrum","ep"),("gold","gp"),("platinum","pp")];
+    let name = name.trim().to_lowercase();
+    let tagged = ["coin","coins","currency"].iter().any(|tag| prop.has_tag(tag));
+    METALS.iter().position(|(metal, short)| name==*short
+        || ["piece","pieces","coin","coins"].iter().any(|noun| name==format!("{} {}",metal,noun))
+        || (tagged && name.split_whitespace().any(|word| word==*metal)))
+}
+///holds the character while it is being parsed, along with anything that has to be put together at the end
+#[derive(Default)]
+struct CharacterBuilder{
+    character: Character,
+    attacks: HashMap<String,Attack>,
+    spell_lists: HashMap<String,SpellList>,
+    ///spells with the ids of the spell and its list, added to the lists once their damage and saves are read
+    spells: Vec<(String,String,Spell)>,
+    spell_index: HashMap<String,usize>,
+    ///features with their own id and the ids of their parent and ancestors, nearest first, to find their source once everything is read
+    features: Vec<(Vec<String>,Feature)>,
+    ///the ids of the classes, races, backgrounds and feats features can be inside of
+    feature_sources: HashMap<String,FeatureSource>,
+    ///items and containers with the ids of their parent and ancestors, nearest first, to find the container they're in
+    items: Vec<(Vec<String>,Item)>,
+    containers: Vec<(Vec<String>,Container)>,
+    ///how many coins a property holds, with the ids of its parent and ancestors like items
+    coins: Vec<(Vec<String>,i64)>,
+    starting_class: String,
+}
+impl CharacterBuilder{
+    ///adds a single creature property to the character, or returns the name of the field that is missing
+    fn add_property(&mut self, prop: &Property)->Result<(),&'static str>{
+        let ch = &mut self.character;
+        if prop.removed(){
+            return Ok(());
+        }
+        if let Some(source)=feature_source(prop){
+            self.feature_sources.insert(prop.id.clone(),source);
+        }
+        match &prop.kind{
+            PropertyKind::Attribute(attr) if attr.attribute_type.as_deref()==Some("ability")=>{
+                ch.ability_scores.push(AbilityScore::new(required(prop.name(),"name")?.to_string(),
+                    required(attr.total_i64(),"total")?));
+            },
+            PropertyKind::Skill(skill)=>{
+                let name = prop.name();
+                let typ = skill.skill_type.as_deref();
+                if name==Some("Initiative"){
+                    ch.initiative=required(skill.value_i64(),"value")?;
+                } else if typ==Some("save"){
+                    let prf=skill.proficiency;
                     let prof = if prf ==Some(0.49) || prf==Some(0.5){
                         Proficiency::Half
                     } else if prf == Some(1.0){
@@ -642,10 +1178,10 @@ impl Character{
                     } else {
                         Proficiency::None
                     };
-                    saving_throws.push(Skill::new(val["name"].as_str().unwrap().to_string(),
-                        val["value"].as_i64().unwrap(),prof));
-                } else if val["skillType"].as_str()==Some("skill"){
-                    let prf=val["proficiency"].as_f64();
+                    ch.saving_throws.push(Skill::new(required(name,"name")?.to_string(),
+                        required(skill.value_i64(),"value")?,prof));
+                } else if typ==Some("skill"){
+                    let prf=skill.proficiency;
                     let prof = if prf.is_some() && prf.unwrap() >= 0.48 && prf.unwrap() <= 0.52{
                         Proficiency::Half
                     } else if prf == Some(1.0){
@@ -655,282 +1191,336 @@ impl Character{
                     } else {
                         Proficiency::None
                     };
-                    skills.push(Skill::new(val["name"].as_str().unwrap().to_string(),
-                        val["value"].as_i64().unwrap(),prof));
-                    if val["name"].as_str()==Some("Perception"){
-                        passive_bonus = val["passiveBonus"].as_i64().unwrap();
+                    let skl = Skill::new(required(name,"name")?.to_string(),required(skill.value_i64(),"value")?,prof);
+                    if name==Some("Perception"){
+                        ch.passive_bonus = required(skill.passive_bonus,"passiveBonus")?;
                     }
-                } else if val["skillType"].as_str()==Some("armor"){
-                    other_profs.0.push(val["name"].as_str().unwrap().to_string());
-                } else if val["skillType"].as_str()==Some("weapon"){
-                    other_profs.1.push(val["name"].as_str().unwrap().to_string());
-                } else if val["skillType"].as_str()==Some("language"){
-                    other_profs.2.push(val["name"].as_str().unwrap().to_string());
-                } else if val["skillType"].as_str()==Some("tool"){
-                    other_profs.3.push(val["name"].as_str().unwrap().to_string());
+                    ch.skills.push(skl);
+                } else if typ==Some("armor"){
+                    ch.other_profs.0.push(required(name,"name")?.to_string());
+                } else if typ==Some("weapon"){
+                    ch.other_profs.1.push(required(name,"name")?.to_string());
+                } else if typ==Some("language"){
+                    ch.other_profs.2.push(required(name,"name")?.to_string());
+                } else if typ==Some("tool"){
+                    ch.other_profs.3.push(required(name,"name")?.to_string());
+                }
+            },
+            PropertyKind::Feature(feat)=>{
+                let text = |text: &Option<InlineText>| text.as_ref().and_then(InlineText::computed).unwrap_or("").trim().to_string();
+                let name = required(prop.name(),"name")?.to_string();
+                let uses = feat.uses.as_ref().and_then(Calculation::value_i64).unwrap_or(-1);
+                if prop.tags.iter().any(|tag| tag.contains("background")){
+                    let summary = feat.summary.as_ref().and_then(|summary| summary.value.as_deref());
+                    ch.background.set_background_feature(Feature::new(name,required(summary,"summary.value")?.to_string(),
+                        text(&feat.description),FeatureSource::Background,uses));
+                } else {
+                    //a feature can be tagged as a feat itself, so its own id goes first
+                    let mut ancestors = ancestor_ids(prop);
+                    ancestors.insert(0,prop.id.clone());
+                    self.features.push((ancestors,Feature::new(name,text(&feat.summary),text(&feat.description),FeatureSource::Other,uses)));
                 }
-            } else if val["type"].as_str()==Some("feature") && 
-                val["tags"].as_array().unwrap().iter().any(|tag| tag.as_str().unwrap().contains("background")){
-                    background.set_background_feature(Feature::new(val["name"].as_str().unwrap().to_string(),
-                    val["summary"]["value"].as_str().unwrap().to_string()));
-            }else if val["type"].as_str()==Some("feature"){
-                features.push(val["name"].as_str().unwrap().to_string());
-            }else if val["type"].as_str()==Some("spellList"){
-                let id = val["_id"].as_str().unwrap();
-                let max_prepared = val["maxPrepared"]["value"].as_i64().unwrap_or(0);
-                let dc = val["dc"]["value"].as_i64().unwrap_or(10);
-                let attack_bonus = val["attackRollBonus"]["value"].as_i64().unwrap_or(0);
-                let name = val["name"].as_str().unwrap();
-                spell_ls_dict.entry(id.to_string())
+            },
+            PropertyKind::SpellList(list)=>{
+                let max_prepared = list.max_prepared.as_ref().and_then(Calculation::value_i64).unwrap_or(0);
+                let dc = list.dc.as_ref().and_then(Calculation::value_i64).unwrap_or(10);
+                let attack_bonus = list.attack_roll_bonus.as_ref().and_then(Calculation::value_i64).unwrap_or(0);
+                let name = required(prop.name(),"name")?;
+                self.spell_lists.entry(prop.id.clone())
                     .or_insert_with(|| SpellList::new(HashMap::new(),name.to_string(),dc,attack_bonus,max_prepared));
-            }else if val["type"].as_str()==Some("spell")&&val["deactivatedByToggle"].as_bool()!=Some(true){
-                let ancestors = val["ancestors"].as_array().unwrap();
+            },
+            PropertyKind::Spell(spell) if prop.deactivated_by_toggle!=Some(true)=>{
                 let mut spl_list_id: String = String::new();
-                for anc in ancestors.iter().rev(){
-                    if spell_ls_dict.contains_key(anc["id"].as_str().unwrap_or("")){
-                        spl_list_id=anc["id"].as_str().unwrap().to_string();
+                for anc in prop.ancestors.iter().rev(){
+                    if self.spell_lists.contains_key(&anc.id){
+                        spl_list_id=anc.id.clone();
                         break;
                     }
                 }
                 // assume this always works
-                let name = val["name"].as_str().unwrap_or("").to_string();
-                let lvl = val["level"].as_i64().unwrap_or(0);
-                let casting_time = val["actionType"].as_str().unwrap_or("").to_string();
-                let duration = val["duration"].as_str().unwrap_or("").to_string();
-                let school = val["school"].as_str().unwrap_or("").to_string();
-                let range = val["range"].as_str().unwrap_or("").to_string();
-                let vscr =(val["verbal"].as_bool()==Some(true),val["somatic"].as_bool()==Some(true),
-                    val["concentration"].as_bool()==Some(true),val["ritual"].as_bool()==Some(true));
-                let material = match val["material"].as_str(){
-                    Some(s)=>s.to_string(),
-                    None=>String::new()
-                };
-                let casting_time = if &casting_time=="action"{
+                let name = prop.name().unwrap_or("").to_string();
+                let lvl = spell.level.unwrap_or(0);
+                let casting_time = spell.action_type.as_deref().unwrap_or("");
+                let duration = spell.duration.as_deref().unwrap_or("");
+                let school = spell.school.clone().unwrap_or_default();
+                let range = spell.range.as_deref().unwrap_or("");
+                let vscr =(spell.verbal==Some(true),spell.somatic==Some(true),
+                    spell.concentration==Some(true),spell.ritual==Some(true));
+                let material = spell.material.clone().unwrap_or_default();
+                let casting_time = if casting_time=="action"{
                     ActionType::Action
-                } else if &casting_time=="bonus"{
+                } else if casting_time=="bonus"{
                     ActionType::Bonus
                 } else if casting_time.contains("reaction"){
                     ActionType::Reaction
-                } else if &casting_time=="free"{
+                } else if casting_time=="free"{
                     ActionType::Free
                 } else {
-                    ActionType::Long(val["castingTime"].as_str().unwrap_or("long").replace("round","rnd").replace("minute","min").replace("hour","hr"))
+                    ActionType::Long(spell.casting_time.as_deref().unwrap_or("long").replace("round","rnd").replace("minute","min").replace("hour","hr"))
                 };
                 let duration = duration.to_lowercase().replace("up to ","").replace("round","rnd").replace("minute","min").replace("hour","hr");
                 let range = range.replace("feet","ft").replace("miles","mi").replace("mile","mi").replace("slotLevel","sl")
                     .replace("foot","ft").replace("radius","rad").replace(" * (1 + spellSniper)","");
                 let mut spl = Spell::new(name,lvl,casting_time,duration, school, range, vscr, material);
-                if val["alwaysPrepared"].as_bool() == Some(true){
+                if spell.always_prepared == Some(true){
                     spl.always_prepare();
-                } else if val["prepared"].as_bool() == Some(true){
+                } else if spell.prepared == Some(true){
                     spl.prepare();
                 }
-                let _=spell_ls_dict.entry(spl_list_id.to_string()).and_modify(|ls|{
-                    ls.levels.entry(lvl).and_modify(|splvl| {splvl.add_spell(spl.clone());}).or_insert_with(|| SpellLevel::new(lvl,vec![spl]));}
-                );
-            }else if val["type"].as_str()==Some("damageMultiplier") && val["inactive"].as_bool()!=Some(true){
-                let mult = val["value"].as_f64().unwrap();
-                if mult == 0.0 {
-                    for typ in val["damageTypes"].as_array().unwrap_or(&vec![]){
-                        damage_mults.push(DamageMult::Immune(typ.as_str().unwrap().to_string()));
-                    }
-                } else if mult == 0.5 {
-                    for typ in val["damageTypes"].as_array().unwrap_or(&vec![]){
-                        damage_mults.push(DamageMult::Resist(typ.as_str().unwrap().to_string()));
-                    }
-                } else if mult == 2.0 {
-                    for typ in val["damageTypes"].as_array().unwrap_or(&vec![]){
-                        damage_mults.push(DamageMult::Vuln(typ.as_str().unwrap().to_string()));
+                if let Some(description)=spell.description.as_ref().or(spell.summary.as_ref()).and_then(InlineText::computed){
+                    spl.set_description(description);
+                }
+                self.spell_index.insert(prop.id.clone(),self.spells.len());
+                self.spells.push((prop.id.clone(),spl_list_id,spl));
+            },
+            PropertyKind::SavingThrow(save)=>{
+                if let (Some(spl), Some(stat)) = (self.spell_of(prop), save.stat.as_deref()){
+                    let mut stat = stat.to_string();
+                    if let Some(first)=stat.get_mut(0..1){
+                        first.make_ascii_uppercase();
                     }
+                    spl.set_save(stat);
                 }
-            }else if val["type"].as_str()==Some("note"){
-                let failsafe = String::new();
-                if val["name"].as_str()==Some("Flaws"){
-                    traits.3 = match val["summary"]["text"].as_str(){
-                        Some(s)=>s.to_string(),
-                        None=>failsafe
-                    };
-                } else if val["name"].as_str()==Some("Ideals"){
-                    traits.1 = match val["summary"]["text"].as_str(){
-                        Some(s)=>s.to_string(),
-                        None=>failsafe
-                    };
-                } else if val["name"].as_str()==Some("Personality Traits"){
-                    traits.0 =match val["summary"]["text"].as_str(){
-                        Some(s)=>s.to_string(),
-                        None=>failsafe
-                    };
-                } else if val["name"].as_str()==Some("Bonds"){
-                    traits.2 = match val["summary"]["text"].as_str(){
-                        Some(s)=>s.to_string(),
-                        None=>failsafe
-                    };
+            },
+            PropertyKind::DamageMultiplier(mult) if !prop.inactive()=>{
+                let value = required(mult.value,"value")?;
+                let types = mult.damage_types.iter().cloned();
+                if value == 0.0 {
+                    ch.damage_mults.extend(types.map(DamageMult::Immune));
+                } else if value == 0.5 {
+                    ch.damage_mults.extend(types.map(DamageMult::Resist));
+                } else if value == 2.0 {
+                    ch.damage_mults.extend(types.map(DamageMult::Vuln));
+                }
+            },
+            PropertyKind::Note(note)=>{
+                let text = note.summary.as_ref().and_then(|summary| summary.text.clone()).unwrap_or_default();
+                match prop.name(){
+                    Some("Flaws")=>ch.traits.3 = text,
+                    Some("Ideals")=>ch.traits.1 = text,
+                    Some("Personality Traits")=>ch.traits.0 = text,
+                    Some("Bonds")=>ch.traits.2 = text,
+                    _=>{}
                 }
-            }else if val["type"].as_str()==Some("attribute") && val["attributeType"].as_str()==Some("hitDice"){
-                let total: i64 = val["total"].as_i64().unwrap();
+            },
+            PropertyKind::Attribute(attr) if attr.attribute_type.as_deref()==Some("hitDice")=>{
+                let total: i64 = required(attr.total_i64(),"total")?;
                 if total>0{
-                    let ds: String = val["hitDiceSize"].as_str().unwrap().to_string();
-                    let size: i64=ds.split('d').collect::<Vec<_>>()[1].parse().unwrap();
-                    hit_dice.push(Die::new(size,total));
+                    let ds = required(attr.hit_dice_size.as_deref(),"hitDiceSize")?;
+                    let size: i64=required(ds.split('d').nth(1).and_then(|size| size.trim().parse().ok()),"hitDiceSize")?;
+                    ch.hit_dice.push(Die::new(size,total));
                 }
-            }else if val["type"].as_str()==Some("action"){
-                if val["actionType"].as_str()==Some("attack") && val["inactive"].as_bool()!=Some(true){
-                    let bns = AtkBonus::Bonus(val["attackRoll"]["value"].as_i64().unwrap_or(0));
-                    let id = val["_id"].as_str().unwrap().to_string();
-                    let dmg = match attacks_dict.get(&id){
+            },
+            PropertyKind::Action(action)=>{
+                if action.action_type.as_deref()==Some("attack") && !prop.inactive(){
+                    let bns = AtkBonus::Bonus(action.attack_roll.as_ref().and_then(Calculation::value_i64).unwrap_or(0));
+                    let name = required(prop.name(),"name")?.to_string();
+                    let dmg = match self.attacks.get(&prop.id){
                         Some(atk)=>atk.damage(),
                         None=>""
                     };
-                    attacks_dict.insert(id,Attack::new(val["name"].as_str().unwrap().to_string(),bns,dmg.to_string()));
-                } else if val["inactive"].as_bool()!=Some(true){
-                    let typ = val["actionType"].as_str();
-                    let name = val["name"].as_str().unwrap().to_string();
-                    let mut uses = -1;
-                    if let Some(k)=val["uses"]["value"].as_i64(){
-                        uses=k;
-                    }
-                    let typ = if typ==Some("free"){
-                        ActionType::Free
-                    } else if typ==Some("bonus"){
-                        ActionType::Bonus
-                    } else if typ==Some("reaction"){
-                        ActionType::Reaction
-                    } else if typ==Some("action"){
-                        ActionType::Action
-                    } else if typ==Some("event"){
-                        continue
-                    }else if typ==Some("long"){
-                        ActionType::Long("lng".to_string())
-                    } else{
-                        ActionType::default()
+                    self.attacks.insert(prop.id.clone(),Attack::new(name,bns,dmg.to_string()));
+                } else if !prop.inactive(){
+                    let typ = action.action_type.as_deref();
+                    let name = required(prop.name(),"name")?.to_string();
+                    let uses = action.uses.as_ref().and_then(Calculation::value_i64).unwrap_or(-1);
+                    let typ = match typ{
+                        Some("free")=>ActionType::Free,
+                        Some("bonus")=>ActionType::Bonus,
+                        Some("reaction")=>ActionType::Reaction,
+                        Some("action")=>ActionType::Action,
+                        Some("event")=>return Ok(()),
+                        Some("long")=>ActionType::Long("lng".to_string()),
+                        _=>ActionType::default(),
                     };
-                    actions.push(Action{name,uses,typ});
+                    ch.actions.push(Action{name,uses,typ});
                 }
-            }else if val["type"].as_str()==Some("damage"){
-                let par_id = val["parent"]["id"].as_str().unwrap().to_string();
-                let dmg_die = val["amount"]["calculation"].as_str().unwrap_or("0d0");
-                let dmg_bonus = val["amount"]["effects"][0]["amount"]["value"].as_i64().unwrap_or(0);
-                let dmg_type = val["damageType"].as_str().unwrap().to_string();
+            },
+            PropertyKind::Damage(damage)=>{
+                let par_id = &required(prop.parent.as_ref(),"parent.id")?.id;
+                let damage_type = required(damage.damage_type.as_deref(),"damageType")?;
+                let amount = damage.amount.clone().unwrap_or_default();
+                let dmg_die = amount.calculation.as_deref().unwrap_or("0d0");
+                let dmg_bonus = amount.effects.first().and_then(|effect| effect.amount.as_ref())
+                    .and_then(Calculation::value_i64).unwrap_or(0);
                 let dmg_string = format!("{}{}{}[{}]",dmg_die,if dmg_bonus>=0 {"+"} else {""},
-                dmg_bonus,damage_type_abreviator(dmg_type));
-                if let Some(atk)=attacks_dict.get_mut(&par_id){
+                dmg_bonus,damage_type_abreviator(damage_type.to_string()));
+                if let Some(atk)=self.attacks.get_mut(par_id){
                     atk.add_dmg(dmg_string);
                 }
-            }else if val["type"].as_str()==Some("class"){
-                classes.push(Class::new(val["name"].as_str().unwrap().to_string(),
-                    val["level"].as_i64().unwrap()));
-            }else if val["type"].as_str()==Some("item"){
-                if val["name"].as_str().unwrap().to_lowercase().contains("piece"){
-                    let coin_type=val["name"].as_str().unwrap().to_string().to_lowercase();
-                    if coin_type.contains("platinum"){
-                        coins.4 = val["quantity"].as_i64().unwrap();
-                    } else if coin_type.contains("gold"){
-                        coins.3 = val["quantity"].as_i64().unwrap();
-                    } else if coin_type.contains("electrum"){
-                        coins.2 = val["quantity"].as_i64().unwrap();
-                    }else if coin_type.contains("silver"){
-                        coins.1 = val["quantity"].as_i64().unwrap();
-                    } else if coin_type.contains("copper"){
-                        coins.0 = val["quantity"].as_i64().unwrap();
+                if let Some(spl)=self.spell_of(prop){
+                    let bonus = if dmg_bonus==0 {String::new()} else {bns_translator(dmg_bonus)};
+                    spl.add_damage(format!("{}{} {}",dmg_die,bonus,damage_type));
+                }
+            },
+            PropertyKind::Class(class)=>{
+                ch.classes.push(Class::new(required(prop.name(),"name")?.to_string(),required(class.level,"level")?));
+            },
+            PropertyKind::Item(item)=>{
+                let nme = required(prop.name(),"name")?.to_string();
+                if let Some(kind) = coin_kind(prop, &nme){
+                    let count = required(item.quantity,"quantity")?;
+                    match kind{
+                        0=>ch.coins.0 += count,
+                        1=>ch.coins.1 += count,
+                        2=>ch.coins.2 += count,
+                        3=>ch.coins.3 += count,
+                        _=>ch.coins.4 += count,
                     }
+                    self.coins.push((ancestor_ids(prop),count));
                 }else{
-                    let nme = val["name"].as_str().unwrap().to_string();
-                    let mut itme = Item::new(val["quantity"].as_i64().unwrap_or(0),
-                        val["name"].as_str().unwrap().to_string(),
-                        val["plural"].as_str().unwrap_or(&nme).to_string());
-                    if val["requiresAttunement"].as_bool()==Some(true){
+                    let mut itme = Item::new(item.quantity.unwrap_or(0),
+                        nme.clone(),
+                        item.plural.clone().unwrap_or(nme));
+                    if item.requires_attunement==Some(true){
                         itme.needs_attuned();
                     }
-                    equipment.push(itme);
-                }
-            }else if val["type"].as_str()==Some("attribute") && val["attributeType"].as_str()==Some("spellSlot") {
-                if val["inactive"].as_bool() !=Some(true){
-                    let lvl = val["spellSlotLevel"]["value"].as_i64().unwrap();
-                    let num = val["value"].as_i64().unwrap();
-                    spell_slots[(lvl-1) as usize]+=num;
-                }
-            }else if val["type"].as_str()==Some("attribute") && val["attributeType"].as_str()==Some("resource") {
-                if val["inactive"].as_bool() !=Some(true){
-                    resources.push(Resource::new(val["name"].as_str().unwrap().to_string(),
-                    val["total"].as_i64().unwrap_or(0)));
+                    if item.attuned==Some(true){
+                        itme.attune();
+                    }
+                    if item.equipped==Some(true){
+                        itme.equip();
+                    }
+                    itme.set_weight(item.weight.unwrap_or(0.0));
+                    itme.set_value(item.value.unwrap_or(0.0));
+                    self.items.push((ancestor_ids(prop),itme));
                 }
-            }else if val["name"].as_str()==Some("Proficiency Bonus"){
-                prof_bonus=val["total"].as_i64().unwrap();
-            } else if val["name"].as_str()==Some("Speed") && val["type"].as_str()==Some("attribute"){
-                speed=val["total"].as_i64().unwrap();
-            } else if val["name"].as_str()==Some("Hit Points") && val["type"].as_str()==Some("attribute"){
-                hit_points=val["total"].as_i64().unwrap();
-            } else if val["name"].as_str()==Some("Armor Class") && val["type"].as_str()==Some("attribute"){
-                ac=val["total"].as_i64().unwrap();
-            } else if val["tags"].as_array().unwrap().iter().any(|tag| tag.as_str()==Some("background")){
-                background=Background::new(val["name"].as_str().unwrap().to_string());
-            } else if val["type"].as_str()==Some("constant"){
-                if val["variableName"].as_str()==Some("race"){
-                    if race==String::new(){
-                        race = val["calculation"].as_str().unwrap().to_string().replace('\"',"");
+            },
+            PropertyKind::Container(container)=>{
+                let name = required(prop.name(),"name")?.to_string();
+                self.containers.push((ancestor_ids(prop),Container::new(prop.id.clone(),name,container.weight.unwrap_or(0.0),
+                    container.value.unwrap_or(0.0),container.carried!=Some(false),container.contents_weightless==Some(true))));
+            },
+            PropertyKind::Attribute(attr) if attr.attribute_type.as_deref()==Some("spellSlot")=>{
+                if !prop.inactive(){
+                    let lvl = required(attr.spell_slot_level.as_ref().and_then(Calculation::value_i64),"spellSlotLevel.value")?;
+                    let num = required(attr.value_i64(),"value")?;
+                    if !(1..=9).contains(&lvl){
+                        return Err("spellSlotLevel.value");
                     }
-                } else if val["variableName"].as_str()==Some("subRace"){
-                    race = val["calculation"].as_str().unwrap().to_string().replace('\"',"");
-                } else if val["variableName"].as_str()==Some("startingClass"){
-                    starting_class = val["calculation"].as_str().unwrap().trim().to_string();
+                    ch.spell_slots[(lvl-1) as usize]+=num;
                 }
-            } else if val["tags"].as_array().unwrap().iter().any(|tag| tag.as_str()==Some("race")){
-                if race==String::new(){
-                    race = val["name"].as_str().unwrap().to_string();
+            },
+            PropertyKind::Attribute(attr) if attr.attribute_type.as_deref()==Some("resource")=>{
+                if !prop.inactive(){
+                    ch.resources.push(Resource::new(required(prop.name(),"name")?.to_string(),
+                    attr.total_i64().unwrap_or(0)));
                 }
-            } else if val["tags"].as_array().unwrap().iter().any(|tag| tag.as_str()==Some("subrace")){
-                let subrace = val["name"].as_str().unwrap().to_string();
-                race = if subrace.contains(&race){subrace} else {subrace +&race};
+            },
+            _=>self.add_other(prop)?,
+        }
+        Ok(())
+    }
+    ///handles properties we only recognise by name, tag, or variable name
+    fn add_other(&mut self, prop: &Property)->Result<(),&'static str>{
+        let ch = &mut self.character;
+        let is_attribute = matches!(prop.kind, PropertyKind::Attribute(_));
+        let attr_total = match &prop.kind{
+            PropertyKind::Attribute(attr)=>attr.total_i64(),
+            _=>None,
+        };
+        if prop.name()==Some("Proficiency Bonus"){
+            ch.prof_bonus=required(attr_total,"total")?;
+        } else if prop.name()==Some("Speed") && is_attribute{
+            ch.speed=required(attr_total,"total")?;
+        } else if prop.name()==Some("Hit Points") && is_attribute{
+            ch.hit_points=required(attr_total,"total")?;
+        } else if prop.name()==Some("Armor Class") && is_attribute{
+            ch.ac=required(attr_total,"total")?;
+        } else if prop.has_tag("background"){
+            ch.background=Background::new(required(prop.name(),"name")?.to_string());
+        } else if let PropertyKind::Constant(constant)=&prop.kind{
+            let calculation = constant.calculation.as_deref();
+            match constant.variable_name.as_deref(){
+                Some("race") if ch.race.is_empty()=>ch.race = required(calculation,"calculation")?.replace('\"',""),
+                Some("subRace")=>ch.race = required(calculation,"calculation")?.replace('\"',""),
+                Some("startingClass")=>self.starting_class = required(calculation,"calculation")?.trim().to_string(),
+                _=>{}
+            }
+        } else if prop.has_tag("race"){
+            if ch.race.is_empty(){
+                ch.race = required(prop.name(),"name")?.to_string();
             }
+        } else if prop.has_tag("subrace"){
+            let subrace = required(prop.name(),"name")?.to_string();
+            ch.race = if subrace.contains(&ch.race){subrace} else {subrace +&ch.race};
         }
-        for class in classes.iter_mut(){
-            if class.name().to_lowercase() == starting_class.replace('\"',"").replace('\'',"").to_lowercase(){
+        Ok(())
+    }
+    ///the spell a property belongs to, if it is part of one
+    fn spell_of(&mut self, prop: &Property)->Option<&mut Spell>{
+        let i = prop.parent.iter().chain(prop.ancestors.iter().rev()).find_map(|anc| self.spell_index.get(&anc.id))?;
+        Some(&mut self.spells[*i].2)
+    }
+    ///puts together everything that depends on more than one property
+    #[allow(clippy::collapsible_str_replace)]
+    fn build(mut self, race_decoder: Value)->Character{
+        let mut character = self.character;
+        for class in character.classes.iter_mut(){
+            if class.name().to_lowercase() == self.starting_class.replace('\"',"").replace('\'',"").to_lowercase(){
                 class.start_class=true;
                 break;
             }
         }
-        let race = race_translator(race,race_decoder);
-        for pair in attacks_dict.into_iter(){
+        character.race = race_translator(character.race,race_decoder);
+        for (_, spl_list_id, spl) in self.spells{
+            let lvl = spl.level();
+            let _=self.spell_lists.entry(spl_list_id).and_modify(|ls|{
+                ls.levels.entry(lvl).and_modify(|splvl| {splvl.add_spell(spl.clone());}).or_insert_with(|| SpellLevel::new(lvl,vec![spl]));}
+            );
+        }
+        for (ancestors, mut feat) in self.features{
+            feat.source = ancestors.iter().find_map(|id| self.feature_sources.get(id).cloned()).unwrap_or_default();
+            character.features.push(feat);
+        }
+        let container_ids: HashSet<String> = self.containers.iter().map(|(_, container)| container.id().clone()).collect();
+        let container_of = |ancestors: &[String]| ancestors.iter().find(|id| container_ids.contains(*id)).cloned().unwrap_or_default();
+        for (ancestors, mut container) in self.containers{
+            container.set_container(container_of(&ancestors));
+            character.containers.push(container);
+        }
+        for (ancestors, mut item) in self.items{
+            item.set_container(container_of(&ancestors));
+            character.equipment.push(item);
+        }
+        for (ancestors, count) in self.coins{
+            let container = container_of(&ancestors);
+            if !container.is_empty(){
+                character.container_coins.push((container, count));
+            }
+        }
+        for pair in self.attacks.into_iter(){
             if !pair.1.name().is_empty(){
-                attacks.push(pair.1);
+                character.attacks.push(pair.1);
             }
         }
-        let mut spell_lists: Vec<SpellList>= vec![];
-        for pair in spell_ls_dict.into_iter(){
+        for pair in self.spell_lists.into_iter(){
             if !pair.1.name.is_empty(){
-                spell_lists.push(pair.1);
+                character.spell_lists.push(pair.1);
             }
         }
-        Character{
-            char_name,
-            classes,
-            background,
-            damage_mults,
-            race,
-            alignment,
-            xp,
-            ability_scores,
-            prof_bonus,
-            saving_throws,
-            skills,
-            ac,
-            passive_bonus,
-            initiative,
-            speed,
-            hit_points,
-            hit_dice,
-            attacks,
-            actions,
-            resources,
-            equipment,
-            traits,
-            features,
-            other_profs,
-            coins,
-            spell_lists,
-            spell_slots,
-            char_img
-        }
+        //hash map order changes between runs, sort so the same json always gives an equal character
+        character.attacks.sort();
+        character.spell_lists.sort();
+        character
+    }
+}
+///the ids of the parent and ancestors of the property, nearest first
+fn ancestor_ids(prop: &Property)->Vec<String>{
+    prop.parent.iter().chain(prop.ancestors.iter().rev()).map(|anc| anc.id.clone()).collect()
+}
+///the source of the features inside a property, if it is a class or tagged as a race, background or feat
+fn feature_source(prop: &Property)->Option<FeatureSource>{
+    if let (PropertyKind::Class(_), Some(name)) = (&prop.kind, prop.name()){
+        Some(FeatureSource::Class(name.to_string()))
+    } else if prop.has_tag("race") || prop.has_tag("subrace"){
+        Some(FeatureSource::Race)
+    } else if prop.has_tag("background"){
+        Some(FeatureSource::Background)
+    } else if prop.has_tag("feat"){
+        Some(FeatureSource::Feat)
+    } else {
+        None
     }
 }
 fn damage_type_abreviator(typ: String)->String{
@@ -0,0 +1,284 @@
+//! Renders a `Character` as a single self contained html page, following the layout of the pdf:
+//! the three column first page, the equipment and background page, then the spell pages.
+//! Fonts and the portrait are embedded as data urls so the file can be opened anywhere, and the
+//! stylesheet stacks the columns on narrow screens while still printing one sheet per page
+use crate::bns_translator;
+use crate::holding_structs::*;
+use crate::sheet::{equipment_groups,item_status,ordinal,proficiency_translator,value_text,weight_text,ABILITIES,PASS_TIME};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::fmt::Write;
+use std::{fs,io};
+
+const STYLE: &str = r#"
+body{font-family:Roboto,sans-serif;margin:0;background:#eee;color:#000}
+.symbol{font-family:'Noto Sans Symbols 2',sans-serif}
+.page{background:#fff;max-width:8.5in;margin:1em auto;padding:0.4in;box-sizing:border-box}
+.box{border:1px solid #000;border-radius:4px;padding:4px;margin:4px 0}
+.label{font-size:7pt;font-weight:bold;text-align:center;text-transform:uppercase}
+.center{text-align:center}
+.big{font-size:20pt;text-align:center}
+.detail{display:grid;grid-template-columns:1fr 2fr;gap:8px;align-items:center}
+.detail-right{display:grid;grid-template-columns:2fr 2fr 1fr;column-gap:6px}
+.detail-right .label{text-align:left;border-top:1px solid #000;margin-bottom:4px}
+.name{font-size:16pt;text-align:center}
+.columns{display:grid;grid-template-columns:1fr 1fr 1fr;gap:8px}
+.left{display:grid;grid-template-columns:1fr 2fr;gap:6px;align-items:start}
+.score .value{border:1px solid #000;border-radius:8px;width:3em;margin:0 auto;text-align:center;font-size:9pt}
+.stat{display:grid;grid-template-columns:2.5em 1fr;align-items:center}
+.stat>span:first-child{border:1px solid #000;border-radius:4px;text-align:center;padding:2px}
+.skill{font-size:8pt;white-space:nowrap}
+.three{display:grid;grid-template-columns:1fr 1fr 1fr;gap:6px}
+.two{display:grid;grid-template-columns:1fr 1fr;gap:6px}
+.blank{min-height:4em}
+table{width:100%;border-collapse:collapse;font-size:9pt}
+th{font-size:7pt;text-align:left;text-transform:uppercase}
+td{padding:1px 2px;vertical-align:top}
+.attacks td:first-child{font-weight:bold}
+.lines p{margin:2px 0;text-align:center;font-size:9pt}
+.equipment{display:grid;grid-template-columns:1fr 9fr;gap:6px}
+.page2{display:grid;grid-template-columns:3fr 1fr;gap:8px}
+.portrait img{max-width:100%;display:block;margin:0 auto}
+.slots{display:grid;grid-template-columns:repeat(9,1fr);gap:4px}
+.spell-header{display:grid;grid-template-columns:3fr 1fr 1fr 1fr;gap:6px;align-items:stretch}
+.spell-header .name{font-weight:bold;font-size:14pt}
+.level{font-weight:bold;font-size:11pt;margin:6px 0 2px}
+@media (max-width:700px){
+    .columns,.page2,.detail{grid-template-columns:1fr}
+    .page{padding:0.5em;margin:0}
+}
+@media print{
+    @page{size:letter;margin:0.3in}
+    body{background:none}
+    .page{margin:0;padding:0;max-width:none;break-after:page}
+    .page:last-child{break-after:auto}
+    .columns{grid-template-columns:1fr 1fr 1fr}
+    .page2{grid-template-columns:3fr 1fr}
+    .detail{grid-template-columns:1fr 2fr}
+}
+"#;
+
+/// renders the character as html, with the fonts from ./fonts and the portrait(if any) embedded.
+/// #Example
+/// ```
+/// use dicecloud_sheet_printer::html_sheet::render_html;
+/// use dicecloud_sheet_printer::holding_structs::Character;
+///
+/// let mut character = Character::default();
+/// character.char_name = "Test & Co".to_string();
+/// let html = render_html(&character, None).unwrap();
+/// assert!(html.starts_with("<!DOCTYPE html>"));
+/// assert!(html.contains("Test &amp; Co"));
+/// ```
+/// #Errors
+/// fails if the fonts can't be read or the portrait can't be encoded
+pub fn render_html(character: &Character, portrait: Option<&image::DynamicImage>)->io::Result<String>{
+    let mut out = String::new();
+    let _ = write!(out,"<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+        <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>\n",escape(&character.char_name));
+    for (family, weight, path) in [("Roboto","normal","./fonts/Roboto/Roboto-Regular.ttf"),("Roboto","bold","./fonts/Roboto/Roboto-Bold.ttf"),
+        ("Noto Sans Symbols 2","normal","./fonts/Noto_Sans_Symbols_2/NotoSansSymbols2-Regular.ttf")]{
+        let font = fs::read(path)?;
+        let _ = writeln!(out,"@font-face{{font-family:'{}';font-weight:{};src:url(data:font/ttf;base64,{}) format('truetype')}}",
+            family,weight,STANDARD.encode(font));
+    }
+    out.push_str(STYLE);
+    out.push_str("</style>\n</head>\n<body>\n");
+    first_page(&mut out, character);
+    second_page(&mut out, character, portrait)?;
+    spell_pages(&mut out, character);
+    out.push_str("</body>\n</html>\n");
+    Ok(out)
+}
+fn first_page(out: &mut String, character: &Character){
+    let mut classes = character.classes.clone();
+    classes.sort();
+    let class_str = classes.iter().map(|class| format!("{} {}",class.name(),class.level())).collect::<Vec<_>>().join(" / ");
+    let xp = if character.xp>0 {character.xp.to_string()} else {String::new()};
+    out.push_str("<div class=\"page\">\n<div class=\"detail\">\n");
+    let _ = writeln!(out,"<div class=\"box name\">{}</div>",escape(&character.char_name));
+    out.push_str("<div class=\"box detail-right\">\n");
+    for value in [&class_str,character.background.name(),&String::new()]{
+        let _ = writeln!(out,"<div>{}&nbsp;</div>",escape(value));
+    }
+    out.push_str("<div class=\"label\">Class</div><div class=\"label\">Background</div><div class=\"label\">Player Name</div>\n");
+    for value in [&character.race,&character.alignment,&xp]{
+        let _ = writeln!(out,"<div>{}&nbsp;</div>",escape(value));
+    }
+    out.push_str("<div class=\"label\">Race</div><div class=\"label\">Alignment</div><div class=\"label\">Experience Points</div>\n");
+    out.push_str("</div>\n</div>\n<div class=\"columns\">\n");
+
+    //left column: scores, saves, skills and proficiencies
+    out.push_str("<div>\n<div class=\"left\">\n<div>\n");
+    for name in ABILITIES{
+        //left blank like the pdf when the character doesn't have the score
+        let score = character.ability_scores.iter().find(|score| score.name()==name);
+        let _ = writeln!(out,"<div class=\"box score\"><div class=\"label\">{}</div><div class=\"big\">{}&nbsp;</div><div class=\"value\">{}&nbsp;</div></div>",
+            name,score.map(|score| bns_translator(score.modifier())).unwrap_or_default(),score.map(|score| score.score().to_string()).unwrap_or_default());
+    }
+    out.push_str("</div>\n<div>\n");
+    out.push_str("<div class=\"stat\"><span>&nbsp;</span><span class=\"label\">Inspiration</span></div>\n");
+    let _ = writeln!(out,"<div class=\"stat\"><span>{}</span><span class=\"label\">Proficiency Bonus</span></div>",bns_translator(character.prof_bonus));
+    out.push_str("<div class=\"box\">\n");
+    for name in ABILITIES{
+        match character.saving_throws.iter().find(|save| save.name().replace(" Save","")==name){
+            Some(save)=>skill_line(out, save),
+            None=>{
+                let _ = writeln!(out,"<div class=\"skill\"><span class=\"symbol\">{}</span> &nbsp;&nbsp; {} Save</div>",proficiency_translator(&Proficiency::None),name);
+            },
+        }
+    }
+    out.push_str("<div class=\"label\">Saving Throws</div>\n</div>\n<div class=\"box\">\n");
+    let mut skills = character.skills.clone();
+    skills.sort();
+    for skill in skills.iter(){
+        skill_line(out, skill);
+    }
+    out.push_str("<div class=\"label\">Skills</div>\n</div>\n</div>\n</div>\n");
+    let _ = writeln!(out,"<div class=\"stat\"><span>{}</span><span class=\"label\">Passive Perception</span></div>",character.passive_perception());
+    let (armor, weapons, languages, tools) = &character.other_profs;
+    out.push_str("<div class=\"box lines\">\n");
+    for (name, profs) in [("Armor",armor),("Weapons",weapons),("Languages",languages),("Tools",tools)]{
+        let _ = writeln!(out,"<p><b>{}:</b> {}</p>",name,escape(&profs.join(", ")));
+    }
+    out.push_str("<div class=\"label\">Other Proficiencies &amp; Languages</div>\n</div>\n</div>\n");
+
+    //middle column: combat stats and attacks
+    out.push_str("<div>\n<div class=\"three\">\n");
+    for (value, name) in [(character.ac.to_string(),"Armor Class"),(bns_translator(character.initiative),"Initiative"),(character.speed.to_string(),"Speed")]{
+        let _ = writeln!(out,"<div class=\"box\"><div class=\"big\">{}</div><div class=\"label\">{}</div></div>",value,name);
+    }
+    out.push_str("</div>\n");
+    let _ = writeln!(out,"<div class=\"box\"><div style=\"font-size:7pt\">Hit Point Maximum: {}</div><div class=\"blank\"></div><div class=\"label\">Current Hit Points</div></div>",
+        character.hit_points);
+    out.push_str("<div class=\"box\"><div class=\"blank\"></div><div class=\"label\">Temporary Hit Points</div></div>\n<div class=\"two\">\n");
+    let hd_str = character.hit_dice.iter().map(|die| die.to_string()).collect::<Vec<_>>().join(", ");
+    let _ = writeln!(out,"<div class=\"box\"><div style=\"font-size:7pt\">Total: {}</div><div class=\"blank\"></div><div class=\"label\">Hit Dice</div></div>",escape(&hd_str));
+    out.push_str("<div class=\"box\" style=\"font-size:7pt;text-align:right\">SUCCESSES <span class=\"symbol\">⭘-⭘-⭘</span><br>\
+        FAILURES <span class=\"symbol\">⭘-⭘-⭘</span><div class=\"blank\" style=\"min-height:1.5em\"></div><div class=\"label\">Death Saves</div></div>\n</div>\n");
+    let mut attacks = character.attacks.clone();
+    attacks.sort();
+    out.push_str("<div class=\"box\">\n<table class=\"attacks\">\n<tr><th>Name</th><th>Atk Bonus</th><th>Damage/Type</th></tr>\n");
+    for atk in attacks.iter(){
+        let _ = writeln!(out,"<tr><td>{}</td><td class=\"center\">{}</td><td>{}</td></tr>",escape(atk.name()),atk.bonus_as_string(),escape(atk.damage()));
+    }
+    for _ in attacks.len()..8{
+        out.push_str("<tr><td>&nbsp;</td><td></td><td></td></tr>\n");
+    }
+    out.push_str("</table>\n<div class=\"label\">Attacks &amp; Spellcasting</div>\n</div>\n</div>\n");
+
+    //right column: traits, actions and features
+    out.push_str("<div>\n");
+    let (personality, ideals, bonds, flaws) = &character.traits;
+    for (text, name) in [(personality,"Personality Traits"),(ideals,"Ideals"),(bonds,"Bonds"),(flaws,"Flaws")]{
+        let _ = writeln!(out,"<div class=\"box lines\"><p>{}&nbsp;</p><div class=\"label\">{}</div></div>",escape(text),name);
+    }
+    let mut actions = character.actions.clone();
+    actions.sort();
+    let mut resources = character.resources.clone();
+    resources.sort();
+    let mut dmg_mults = character.damage_mults.clone();
+    dmg_mults.sort();
+    let mut features = character.features.clone();
+    features.sort();
+    out.push_str("<div class=\"box lines\">\n<div class=\"label\">Actions</div>\n");
+    for act in actions.iter().filter(|act| !PASS_TIME.is_match(act.name()))
+        .filter(|act| !character.equipment.iter().any(|x| act.name()==x.name() && act.uses() != -1)){
+        let _ = writeln!(out,"<p>{}</p>",escape(&act.to_string()));
+    }
+    out.push_str("<hr>\n<div class=\"label\">Features</div>\n");
+    let features = resources.iter().map(|r| r.to_string()).chain(dmg_mults.iter().map(|mul| mul.to_string()))
+        .chain(features.into_iter().filter(|feat| !actions.iter().any(|x| feat.name()==x.name())).map(|feat| feat.to_string()));
+    for feat in features{
+        let _ = writeln!(out,"<p>{}</p>",escape(&feat));
+    }
+    out.push_str("</div>\n</div>\n</div>\n</div>\n");
+}
+fn second_page(out: &mut String, character: &Character, portrait: Option<&image::DynamicImage>)->io::Result<()>{
+    out.push_str("<div class=\"page\">\n<div class=\"page2\">\n<div>\n<div class=\"box equipment\">\n<div>\n");
+    let (cp, sp, ep, gp, pp) = character.coins;
+    for (name, num) in [("CP",cp),("SP",sp),("EP",ep),("GP",gp),("PP",pp)]{
+        let _ = writeln!(out,"<div class=\"label\">{}</div><div class=\"box center\">{}</div>",name,num);
+    }
+    out.push_str("</div>\n<div class=\"lines\">\n<div class=\"label\">Equipment</div>\n");
+    for (container, items) in equipment_groups(character){
+        if let Some(cnt)=container{
+            let inside = character.container(cnt.container()).map(|outer| format!(" (in {})",escape(outer.name()))).unwrap_or_default();
+            let weightless = if cnt.contents_weightless() {", weightless"} else {""};
+            let _ = writeln!(out,"<p><b>{}</b>{} <small>{}{}, {}</small></p>",escape(cnt.name()),inside,
+                weight_text(character.contents_weight(cnt.id())),weightless,value_text(character.contents_value(cnt.id())));
+        }
+        for itm in items{
+            let q = itm.quantity();
+            let nme = if q==1 {itm.name()} else {itm.plural_name()};
+            let atn = if itm.requires_attunement() {"<span class=\"symbol\">❂</span> "} else {""};
+            let num = if q==1 {String::new()} else {format!("{} ",q)};
+            let _ = writeln!(out,"<p>{}{}{}<i>{}</i></p>",atn,num,escape(nme),item_status(itm));
+        }
+    }
+    if !character.equipment.is_empty() || !character.containers.is_empty(){
+        let _ = writeln!(out,"<hr>\n<p><b>Total</b> <small>{}, {}</small></p>",
+            weight_text(character.contents_weight("")),value_text(character.contents_value("")));
+    }
+    out.push_str("</div>\n</div>\n</div>\n<div>\n<div class=\"box portrait\">\n");
+    match portrait{
+        Some(img)=>{
+            let mut png = Vec::new();
+            img.write_to(&mut png, image::ImageOutputFormat::Png).map_err(|e| io::Error::new(io::ErrorKind::InvalidData,e))?;
+            let _ = writeln!(out,"<img src=\"data:image/png;base64,{}\" alt=\"portrait of {}\">",STANDARD.encode(png),escape(&character.char_name));
+        },
+        None=>out.push_str("<div class=\"blank\" style=\"min-height:12em\"></div>\n"),
+    }
+    out.push_str("<div class=\"label\">Character Portrait</div>\n</div>\n");
+    let feature = character.background.background_feature();
+    let _ = writeln!(out,"<div class=\"box lines\"><div class=\"label\">Background</div><p><b>{}</b></p><p>{}</p></div>",
+        escape(feature.name()),escape(feature.summary()));
+    out.push_str("</div>\n</div>\n</div>\n");
+    Ok(())
+}
+fn spell_pages(out: &mut String, character: &Character){
+    if character.spell_lists.is_empty(){
+        return;
+    }
+    out.push_str("<div class=\"page\">\n<div class=\"label\" style=\"font-size:12pt;text-align:left\">Spells</div>\n<div class=\"slots\">\n");
+    for (idx, num) in character.spell_slots.iter().enumerate(){
+        let _ = writeln!(out,"<div class=\"box\"><div class=\"label\">{} Level</div><div class=\"center symbol\">{}&nbsp;</div></div>",
+            ordinal(idx as i64+1),"⭘".repeat((*num).max(0) as usize));
+    }
+    out.push_str("</div>\n");
+    let mut spell_lists = character.spell_lists.clone();
+    spell_lists.sort();
+    for ls in spell_lists.iter(){
+        out.push_str("<div class=\"spell-header\">\n");
+        let _ = writeln!(out,"<div class=\"box name center\">{}</div>",escape(&ls.name));
+        let _ = writeln!(out,"<div class=\"box center\">{}<div class=\"label\">Attack Bonus</div></div>",bns_translator(ls.atk_bonus));
+        let _ = writeln!(out,"<div class=\"box center\">DC {}<div class=\"label\">Save DC</div></div>",ls.save_dc);
+        let _ = writeln!(out,"<div class=\"box\" style=\"text-align:right\">/{}<div class=\"label\">Prepared</div></div>",ls.max_prepared);
+        out.push_str("</div>\n<table>\n<tr><th>P</th><th>Name</th><th>School</th><th>Cast Time</th><th>Range</th><th>VSCR</th><th>Dur</th><th>Material</th></tr>\n");
+        for lvl in 0..=ls.max_lvl(){
+            let title = if lvl==0 {"Cantrips".to_string()} else {format!("{} Level Spells",ordinal(lvl))};
+            let _ = writeln!(out,"<tr><td colspan=\"8\" class=\"level\">{}</td></tr>",title.to_uppercase());
+            let Some(spell_level)=ls.levels.get(&lvl) else {continue};
+            let mut spells = spell_level.spells().clone();
+            spells.sort();
+            for spl in spells.iter(){
+                let prepd = match spl.prepd(){
+                    SpellPrep::AlwaysPrepared=>"A".to_string(),
+                    SpellPrep::Prepared=>"<span class=\"symbol\">⦿</span>".to_string(),
+                    SpellPrep::NotPrepared=>"<span class=\"symbol\">⭘</span>".to_string(),
+                };
+                let _ = writeln!(out,"<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    prepd,escape(spl.name()),escape(spl.school()),escape(&spl.casting_time().to_string()),escape(spl.range()),
+                    spl.vscr_to_string(),escape(spl.duration()),escape(spl.material()));
+            }
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</div>\n");
+}
+fn skill_line(out: &mut String, skill: &Skill){
+    let _ = writeln!(out,"<div class=\"skill\"><span class=\"symbol\">{}</span> {} {}</div>",proficiency_translator(skill.prof()),bns_translator(skill.modifier()),escape(skill.name()));
+}
+fn escape(text: &str)->String{
+    text.replace('&',"&amp;").replace('<',"&lt;").replace('>',"&gt;").replace('"',"&quot;").replace('\'',"&#39;")
+}
@@ -4,68 +4,348 @@ use genpdf::{Element, Alignment};
 use genpdf::{elements,fonts, style};
 use image::io::Reader as ImageReader;
 use image::imageops::FilterType;
-use std::io::Cursor;
-use std::process;
+use std::io::{self,Cursor,Read};
+use std::{fs,process};
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
 
+pub mod export;
 pub mod holding_structs;
+pub mod html_sheet;
+pub mod properties;
+pub mod sheet;
+pub mod spell_cards;
+pub mod text_sheet;
 
 
+/// the dicecloud server used unless another one is configured
+pub const DEFAULT_BASE_URL: &str = "https://www.dicecloud.com";
+
+/// the url of an api endpoint on the server at `base_url`, eg `api_url("https://www.dicecloud.com/", "login")`
+/// gives `https://www.dicecloud.com/api/login`
+pub fn api_url(base_url: &str, endpoint: &str)->String{
+    format!("{}/api/{}",base_url.trim().trim_end_matches('/'),endpoint)
+}
+
+///A connection to a dicecloud server. Holds the server address, the login token and a connection pool that is
+///shared by clones, so one client(or clones of it) can fetch many characters without reconnecting each time
+/// #Example
+/// ```no_run
+/// use dicecloud_sheet_printer::DicecloudClient;
+///
+/// # async fn run()->Result<(), dicecloud_sheet_printer::ClientError>{
+/// let mut client = DicecloudClient::new("https://www.dicecloud.com")?;
+/// client.login("username", "password").await?;
+/// let creature = client.creature("tARF8SRLPtQq9cjuw").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug,Clone)]
+pub struct DicecloudClient{
+    base_url: String,
+    token: Option<String>,
+    client: reqwest::Client,
+}
+impl DicecloudClient{
+    /// how long to wait to connect to the server, by default
+    pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+    /// how long to wait for a whole request, by default
+    pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+    /// a client for the server at `base_url` with the default timeouts and no token
+    pub fn new(base_url: &str)->Result<DicecloudClient,ClientError>{
+        DicecloudClient::with_timeouts(base_url, DicecloudClient::CONNECT_TIMEOUT, DicecloudClient::REQUEST_TIMEOUT)
+    }
+    /// a client for the server at `base_url` that gives up connecting after `connect` and on any request after `request`
+    pub fn with_timeouts(base_url: &str, connect: Duration, request: Duration)->Result<DicecloudClient,ClientError>{
+        let client = reqwest::Client::builder()
+            .connect_timeout(connect)
+            .timeout(request)
+            .build()?;
+        Ok(DicecloudClient{base_url: base_url.trim().trim_end_matches('/').to_string(), token: None, client})
+    }
+    pub fn base_url(&self)->&str{
+        &self.base_url
+    }
+    /// the token sent with requests, if logged in
+    pub fn token(&self)->Option<&str>{
+        self.token.as_deref()
+    }
+    /// use a token from somewhere else, or `None` to make anonymous requests
+    pub fn set_token(&mut self, token: Option<String>){
+        self.token = token;
+    }
+    /// logs in, keeping the token for later requests and returning it.
+    /// #Errors
+    /// `InvalidCredentials` if dicecloud rejects the username or password, `RateLimited` after too many attempts,
+    /// `Http` if the server can't be reached and `InvalidResponse` if the answer doesn't look like dicecloud's
+    pub async fn login(&mut self, username: &str, password: &str)->Result<String,ClientError>{
+        let mut map = HashMap::new();
+        map.insert("username",username.trim());
+        map.insert("password",password.trim());
+        let res = self.client.post(api_url(&self.base_url,"login"))
+            .json(&map)
+            .send()
+            .await?;
+        let out = match DicecloudClient::read_json(res).await{
+            Err(ClientError::Api(reason))=>return Err(ClientError::InvalidCredentials(reason)),
+            res=>res?,
+        };
+        match out["token"].as_str(){
+            Some(token)=>{
+                self.token = Some(token.to_string());
+                Ok(token.to_string())
+            },
+            None=>Err(ClientError::InvalidResponse("the login response has no token".to_string())),
+        }
+    }
+    /// fetches the creature json for a character id, the same json `read_character` reads from a file
+    pub async fn creature(&self, character_id: &str)->Result<Value,ClientError>{
+        self.get_json(&get_char_url_at(&self.base_url, character_id.to_string())).await
+    }
+    /// downloads an image, eg the character portrait, scaled to fit on the sheet
+    pub async fn image(&self, img_url: &str)->Result<image::DynamicImage,ClientError>{
+        Ok(portrait_from_bytes(&self.image_bytes(img_url).await?)?)
+    }
+    /// downloads an image as is, to be read later with `portrait_from_bytes`
+    pub async fn image_bytes(&self, img_url: &str)->Result<Vec<u8>,ClientError>{
+        let res_bytes = self.client.get(img_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        Ok(res_bytes.to_vec())
+    }
+    /// a get request to the api, sending the token if there is one
+    async fn get_json(&self, url: &str)->Result<Value,ClientError>{
+        let mut req = self.client.get(url);
+        if let Some(token)=&self.token{
+            req = req.bearer_auth(token);
+        }
+        DicecloudClient::read_json(req.send().await?).await
+    }
+    /// reads a dicecloud api response, turning an `error` field(or a 401/403 status) into `ClientError::Api`
+    /// and meteor's rate limit errors(or a 429 status) into `ClientError::RateLimited`
+    async fn read_json(res: reqwest::Response)->Result<Value,ClientError>{
+        let status = res.status();
+        let retry_after = res.headers().get(reqwest::header::RETRY_AFTER)
+            .and_then(|val| val.to_str().ok())
+            .and_then(|val| val.trim().parse().ok())
+            .map(Duration::from_secs);
+        let txt = res.text().await?;
+        let out: Value = match serde_json::from_str(&txt){
+            Ok(out)=>out,
+            Err(_) if status==reqwest::StatusCode::TOO_MANY_REQUESTS=>return Err(ClientError::RateLimited(retry_after)),
+            Err(_) if status==reqwest::StatusCode::UNAUTHORIZED || status==reqwest::StatusCode::FORBIDDEN=>{
+                return Err(ClientError::Api(status.to_string()));
+            },
+            Err(_) if !status.is_success()=>return Err(ClientError::InvalidResponse(format!("the server answered {}",status))),
+            Err(_) if txt.trim().is_empty()=>return Err(ClientError::InvalidResponse("the response was empty".to_string())),
+            Err(e)=>return Err(ClientError::InvalidResponse(format!("the response is not json({})",e))),
+        };
+        if let Some(err)=out.get("error"){
+            if err.as_str()==Some("too-many-requests") || status==reqwest::StatusCode::TOO_MANY_REQUESTS{
+                let wait = out["details"]["timeToReset"].as_u64().map(Duration::from_millis).or(retry_after);
+                return Err(ClientError::RateLimited(wait));
+            }
+            let msg = out["reason"].as_str().or(out["message"].as_str()).or(err.as_str()).unwrap_or("unknown error");
+            return Err(ClientError::Api(msg.to_string()));
+        }
+        if !status.is_success(){
+            return Err(ClientError::InvalidResponse(format!("the server answered {}",status)));
+        }
+        Ok(out)
+    }
+}
+///What went wrong talking to dicecloud
+#[derive(Debug)]
+pub enum ClientError{
+    ///the request could not be sent or no response arrived, eg the server is down or timed out
+    Http(reqwest::Error),
+    ///dicecloud answered with an error, eg a private or missing character
+    Api(String),
+    ///dicecloud rejected the username or password, with its reason
+    InvalidCredentials(String),
+    ///too many requests were made, with how long to wait before trying again if dicecloud said
+    RateLimited(Option<Duration>),
+    ///dicecloud answered with something that isn't what the api returns
+    InvalidResponse(String),
+    ///a downloaded image could not be read
+    Image(image::ImageError),
+}
+impl fmt::Display for ClientError{
+    fn fmt(&self, f: &mut fmt::Formatter)->fmt::Result{
+        match self{
+            ClientError::Http(e) if e.is_timeout()=>write!(f,"Dicecloud did not respond in time: {}",e),
+            ClientError::Http(e)=>write!(f,"Failed to reach dicecloud: {}",e),
+            ClientError::Api(msg)=>write!(f,"Dicecloud returned an error: {}",msg),
+            ClientError::InvalidCredentials(msg)=>write!(f,"Dicecloud rejected the username or password({})",msg),
+            ClientError::RateLimited(Some(wait))=>write!(f,"Too many requests to dicecloud, try again in {} seconds",wait.as_secs().max(1)),
+            ClientError::RateLimited(None)=>write!(f,"Too many requests to dicecloud, try again later"),
+            ClientError::InvalidResponse(msg)=>write!(f,"Unexpected response from dicecloud({}), check that the base url points to a dicecloud server",msg),
+            ClientError::Image(e)=>write!(f,"Failed to read image: {}",e),
+        }
+    }
+}
+impl Error for ClientError{
+    fn source(&self)->Option<&(dyn Error + 'static)>{
+        match self{
+            ClientError::Http(e)=>Some(e),
+            ClientError::Image(e)=>Some(e),
+            _=>None,
+        }
+    }
+}
+impl From<reqwest::Error> for ClientError{
+    fn from(e: reqwest::Error)->ClientError{
+        ClientError::Http(e)
+    }
+}
+impl From<image::ImageError> for ClientError{
+    fn from(e: image::ImageError)->ClientError{
+        ClientError::Image(e)
+    }
+}
+/// reads a downloaded image in any supported format, scaled to fit the portrait box of the sheet
+pub fn portrait_from_bytes(bytes: &[u8])->Result<image::DynamicImage,image::ImageError>{
+    let img = ImageReader::new(Cursor::new(bytes)).with_guessed_format().map_err(image::ImageError::IoError)?.decode()?;
+    Ok(img.resize(540,2000,FilterType::CatmullRom))
+}
+/// logs in to dicecloud.com, giving an empty token if the username or password is rejected.
+/// Use `get_token_at` for another server or to get the error
+/// #Panics
+/// panics if dicecloud can't be reached or answers with something unexpected
+#[deprecated(note="use `get_token_at` or `DicecloudClient::login`, which return the error")]
 pub async fn get_token(username: String, psw: String)->String{
-    let client = reqwest::Client::new();
-    let mut map =HashMap::new();
-    map.insert("username",username.trim());
-    map.insert("password",psw.trim());
-    let res = client.post("https://www.dicecloud.com/api/login")
-        .json(&map)
-        .send()
-        .await
-        .expect("Failed to reach dicecloud");
-    let txt =res.text().await.expect("failed to get text");
-    if txt.contains("error"){
-        return String::new(); //if login fails, just pretend everything is ok
-    }
-    let js_res: Result<Value,_>= serde_json::from_str(&txt);
-    let token =js_res.expect("Failed to parse Json")["token"].as_str().unwrap().to_string();
-    token
-}
-/// should have charcter_url=https://beta.dicecloud.com/api/creature/<creatureId>
+    match get_token_at(DEFAULT_BASE_URL, username, psw).await{
+        Ok(token)=>token,
+        Err(ClientError::InvalidCredentials(_))=>String::new(),
+        Err(e)=>panic!("{}",e),
+    }
+}
+/// logs in to the server at `base_url` with a throwaway client, see `DicecloudClient::login`
+pub async fn get_token_at(base_url: &str, username: String, psw: String)->Result<String,ClientError>{
+    DicecloudClient::new(base_url)?.login(&username, &psw).await
+}
+/// should have charcter_url=<base url>/api/creature/<creatureId>, as built by `get_char_url`.
+/// Use `DicecloudClient::creature`(or `try_get_character`) to get the error instead
+/// #Panics
+/// exits the program if dicecloud answers with an error, eg for a private character, and panics if it can't be reached
+#[deprecated(note="use `DicecloudClient::creature` or `try_get_character`, which return the error")]
 pub async fn get_character(token: String, character_url: String)->Value{
-    let client= reqwest::Client::new();
-    let bearer = format!("Bearer {}",token);
-    let res = client.get(character_url)
-        .header("Authorization",bearer)
-        .send()
-        .await
-        .expect("Dicecloud failed to respond");
-    
-    let txt=res.text().await.expect("Dicecloud did not respond to request properly");
-    if  txt.trim()== ""{
-        panic!("invalid response");
-    }
-    let out: Value =serde_json::from_str(&txt).expect("bad format");
-    if out["error"].as_str().is_some(){
-        println!("{}. Exiting program",out["error"].as_str().unwrap());
-        process::exit(1);
-    }
-    out
+    match try_get_character(token, character_url).await{
+        Ok(out)=>out,
+        Err(ClientError::Api(reason))=>{
+            println!("{}. Exiting program",reason);
+            process::exit(1);
+        },
+        Err(e)=>panic!("{}",e),
+    }
+}
+/// fetches a character like `get_character` but returns the error, the server is taken from the url.
+/// Prefer `DicecloudClient::creature` to fetch several characters
+/// #Errors
+/// `Api` if dicecloud answers with an error, eg for a private character, and `Http` if it can't be reached
+pub async fn try_get_character(token: String, character_url: String)->Result<Value,ClientError>{
+    let base_url = character_url.split_once("/api/").map_or(DEFAULT_BASE_URL, |(base, _)| base);
+    let mut client = DicecloudClient::new(base_url)?;
+    client.set_token(Some(token));
+    client.get_json(&character_url).await
+}
+/// reads a creature json in the same shape `get_character` returns from a file, or from stdin if the path is `-`
+pub fn read_character(path: &str)->io::Result<Value>{
+    let mut txt = String::new();
+    if path=="-"{
+        io::stdin().read_to_string(&mut txt)?;
+    } else {
+        txt = fs::read_to_string(path)?;
+    }
+    let out: Value = serde_json::from_str(&txt)?;
+    if out["creatures"][0].is_null() || !out["creatureProperties"].is_array(){
+        return Err(io::Error::new(io::ErrorKind::InvalidData,"not a dicecloud creature export"));
+    }
+    Ok(out)
 }
+/// #Panics
+/// panics if the image can't be downloaded or decoded, use `DicecloudClient::image` to get the error instead
+#[deprecated(note="use `DicecloudClient::image`, which returns the error")]
 pub async fn get_img_from_url(img_url: String)->image::DynamicImage{
-    let client = reqwest::Client::new();
-    let res_bytes = client.get(img_url)
-        .send()
-        .await
-        .expect("Failed to send request")
-        .bytes()
-        .await
-        .expect("Failed to get image bytes");
-    ImageReader::new(Cursor::new(res_bytes)).with_guessed_format().expect("Failed to parse format").decode().expect("failed to decode").resize(540,2000,FilterType::CatmullRom)
+    let client = DicecloudClient::new(DEFAULT_BASE_URL).expect("Failed to build the http client");
+    client.image(&img_url).await.expect("Failed to get image")
 }
+/// the url of the creature endpoint for a character on dicecloud.com, see `get_char_url_at`
+/// #Example
+/// ```
+/// use dicecloud_sheet_printer::get_char_url;
+///
+/// assert_eq!(get_char_url("tARF8SRLPtQq9cjuw".to_string()), "https://www.dicecloud.com/api/creature/tARF8SRLPtQq9cjuw");
+/// ```
 pub fn get_char_url(caracter_id: String) -> String{
-    format!("https://www.dicecloud.com/api/creature/{}",caracter_id.trim())
+    get_char_url_at(DEFAULT_BASE_URL, caracter_id)
+}
+/// the url of the creature endpoint for a character on the server at `base_url`. Takes a character id or
+/// a character url, anything else is used as given, use `character_id` first to check it
+/// #Example
+/// ```
+/// use dicecloud_sheet_printer::get_char_url_at;
+///
+/// assert_eq!(get_char_url_at("http://localhost:3000/", " abc123 ".to_string()), "http://localhost:3000/api/creature/abc123");
+/// assert_eq!(get_char_url_at("https://www.dicecloud.com", "https://beta.dicecloud.com/character/tARF8SRLPtQq9cjuw/jsonTest".to_string()),
+///     "https://www.dicecloud.com/api/creature/tARF8SRLPtQq9cjuw");
+/// ```
+pub fn get_char_url_at(base_url: &str, caracter_id: String) -> String{
+    let id = character_id(&caracter_id).unwrap_or_else(|_| caracter_id.trim().to_string());
+    api_url(base_url,&format!("creature/{}",id))
 }
+/// the character id from either the id itself or a character url on any dicecloud server, like
+/// `https://beta.dicecloud.com/character/<id>/<name>`, with or without the name and query string.
+/// Ids are the 17 letters and digits dicecloud generates
+/// #Example
+/// ```
+/// use dicecloud_sheet_printer::character_id;
+///
+/// assert_eq!(character_id(" tARF8SRLPtQq9cjuw ").unwrap(), "tARF8SRLPtQq9cjuw");
+/// assert_eq!(character_id("https://beta.dicecloud.com/character/tARF8SRLPtQq9cjuw/jsonTest?tab=stats").unwrap(), "tARF8SRLPtQq9cjuw");
+/// assert_eq!(character_id("dicecloud.example.com/character/tARF8SRLPtQq9cjuw").unwrap(), "tARF8SRLPtQq9cjuw");
+/// assert!(character_id("jsonTest").is_err());
+/// assert!(character_id("https://beta.dicecloud.com/home").is_err());
+/// ```
+/// #Errors
+/// when the input is neither a character url nor a valid id
+pub fn character_id(input: &str)->Result<String,InvalidCharacterId>{
+    let input = input.trim();
+    let id = match input.split_once("/character/"){
+        Some((_, rest))=>rest.split(['/','?','#']).next().unwrap_or(""),
+        None=>input,
+    };
+    if id.len()==17 && id.chars().all(|c| c.is_ascii_alphanumeric()){
+        Ok(id.to_string())
+    } else {
+        Err(InvalidCharacterId(input.to_string()))
+    }
+}
+///Input that is neither a character id nor a character url, holding the input
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct InvalidCharacterId(pub String);
+impl fmt::Display for InvalidCharacterId{
+    fn fmt(&self, f: &mut fmt::Formatter)->fmt::Result{
+        if self.0.contains('/'){
+            write!(f,"\"{}\" is not a dicecloud character url, it should look like https://dicecloud.com/character/<id>/<name>",self.0)
+        } else {
+            write!(f,"\"{}\" is not a dicecloud character id, the id is the 17 letters and digits after /character/ in the character url",self.0)
+        }
+    }
+}
+impl Error for InvalidCharacterId{}
 pub fn generate_pdf()->genpdf::Document{
+    try_generate_pdf().expect("Failed to load main font")
+}
+/// like `generate_pdf`, but returns an error if the font can't be loaded
+pub fn try_generate_pdf()->Result<genpdf::Document,genpdf::error::Error>{
     //define the default font for the document
-    let font = fonts::from_files("./fonts/Roboto","Roboto",None).expect("Failed to load main font");
+    let font = fonts::from_files("./fonts/Roboto","Roboto",None)?;
     let mut doc = genpdf::Document::new(font);
     //set the title and other basic parameter
     doc.set_title("Character Sheet");
@@ -95,7 +375,7 @@ pub fn generate_pdf()->genpdf::Document{
                 .expect("Failed to load hyphenation data"),
         );
     }
-    doc
+    Ok(doc)
 }
 pub fn bns_translator(b: i64)->String{
     if b>=0{
@@ -0,0 +1,272 @@
+//! Typed versions of the creature properties returned by the dicecloud v2 creature endpoint.
+//! Only the fields the printer uses are modeled, anything else in the json is ignored. Every modeled field is
+//! optional, so a property is only rejected when a field is there with the wrong type or the builder needs a field
+//! it doesn't have. Stats that homebrew content can make fractional, like attribute totals, are read as floats
+use serde::Deserialize;
+use serde_json::Value;
+
+///a creature property, the fields every property type shares along with its type specific fields
+#[derive(Deserialize,Debug,Clone,PartialEq)]
+#[serde(rename_all="camelCase")]
+pub struct Property{
+    #[serde(rename="_id")]
+    pub id: String,
+    #[serde(default)]
+    pub order: Option<i64>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub removed: Option<bool>,
+    #[serde(default)]
+    pub inactive: Option<bool>,
+    #[serde(default)]
+    pub deactivated_by_toggle: Option<bool>,
+    #[serde(default)]
+    pub ancestors: Vec<PropRef>,
+    #[serde(default)]
+    pub parent: Option<PropRef>,
+    ///filled in from the `type` field by `from_json`
+    #[serde(skip)]
+    pub kind: PropertyKind,
+}
+impl Property{
+    ///deserializes a property, picking the type specific fields from its `type`. Each type is deserialized on its
+    ///own rather than through a tagged enum so the error keeps the path to the field that didn't match
+    /// #Example
+    /// ```
+    /// use dicecloud_sheet_printer::properties::{Property, PropertyKind};
+    /// use serde_json::json;
+    ///
+    /// let prop = Property::from_json(&json!({"_id":"abc","type":"attribute","name":"Luck","total":2.5})).unwrap();
+    /// match prop.kind{
+    ///     PropertyKind::Attribute(attr)=>assert_eq!(attr.total, Some(2.5)),
+    ///     _=>panic!("not an attribute"),
+    /// }
+    ///
+    /// let err = Property::from_json(&json!({"_id":"abc","type":"class","name":"Wizard","level":"3"})).unwrap_err();
+    /// assert_eq!(err.path().to_string(), "level");
+    /// ```
+    pub fn from_json(val: &Value)->Result<Property,serde_path_to_error::Error<serde_json::Error>>{
+        let mut prop: Property = serde_path_to_error::deserialize(val)?;
+        prop.kind = match val["type"].as_str(){
+            Some("attribute")=>PropertyKind::Attribute(serde_path_to_error::deserialize(val)?),
+            Some("skill")=>PropertyKind::Skill(serde_path_to_error::deserialize(val)?),
+            Some("action")=>PropertyKind::Action(serde_path_to_error::deserialize(val)?),
+            Some("damage")=>PropertyKind::Damage(serde_path_to_error::deserialize(val)?),
+            Some("spell")=>PropertyKind::Spell(serde_path_to_error::deserialize(val)?),
+            Some("spellList")=>PropertyKind::SpellList(serde_path_to_error::deserialize(val)?),
+            Some("item")=>PropertyKind::Item(serde_path_to_error::deserialize(val)?),
+            Some("container")=>PropertyKind::Container(serde_path_to_error::deserialize(val)?),
+            Some("feature")=>PropertyKind::Feature(serde_path_to_error::deserialize(val)?),
+            Some("note")=>PropertyKind::Note(serde_path_to_error::deserialize(val)?),
+            Some("constant")=>PropertyKind::Constant(serde_path_to_error::deserialize(val)?),
+            Some("class")=>PropertyKind::Class(serde_path_to_error::deserialize(val)?),
+            Some("damageMultiplier")=>PropertyKind::DamageMultiplier(serde_path_to_error::deserialize(val)?),
+            Some("savingThrow")=>PropertyKind::SavingThrow(serde_path_to_error::deserialize(val)?),
+            _=>PropertyKind::Other,
+        };
+        Ok(prop)
+    }
+    pub fn removed(&self)->bool{
+        self.removed==Some(true)
+    }
+    pub fn inactive(&self)->bool{
+        self.inactive==Some(true)
+    }
+    pub fn name(&self)->Option<&str>{
+        self.name.as_deref()
+    }
+    pub fn has_tag(&self,tag: &str)->bool{
+        self.tags.iter().any(|t| t==tag)
+    }
+}
+///the type specific part of a property, selected by its `type` field.
+///Types the printer doesn't use end up as `Other`
+#[derive(Debug,Clone,PartialEq,Default)]
+pub enum PropertyKind{
+    Attribute(Attribute),
+    Skill(SkillProp),
+    Action(ActionProp),
+    Damage(DamageProp),
+    Spell(SpellProp),
+    SpellList(SpellListProp),
+    Item(ItemProp),
+    Container(ContainerProp),
+    Feature(FeatureProp),
+    Note(NoteProp),
+    Constant(ConstantProp),
+    Class(ClassProp),
+    DamageMultiplier(DamageMultiplierProp),
+    SavingThrow(SavingThrowProp),
+    #[default]
+    Other,
+}
+///a reference to another property, as used by `ancestors` and `parent`
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+pub struct PropRef{
+    pub id: String,
+}
+///a field dicecloud computes, like `{"calculation":"1d8","value":4}`
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default)]
+pub struct Calculation{
+    pub calculation: Option<String>,
+    pub value: Option<Value>,
+    pub effects: Vec<CalculationEffect>,
+}
+impl Calculation{
+    ///the computed value, rounded down if it isn't a whole number
+    pub fn value_i64(&self)->Option<i64>{
+        self.value.as_ref().and_then(Value::as_f64).map(|num| num.floor() as i64)
+    }
+}
+///an effect applied to a calculation
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default)]
+pub struct CalculationEffect{
+    pub amount: Option<Calculation>,
+}
+///text with inline calculations, `value` is the text with the calculations filled in
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default)]
+pub struct InlineText{
+    pub text: Option<String>,
+    pub value: Option<String>,
+}
+impl InlineText{
+    ///the text with calculations filled in if dicecloud computed it, the raw text otherwise
+    pub fn computed(&self)->Option<&str>{
+        self.value.as_deref().or(self.text.as_deref())
+    }
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default,rename_all="camelCase")]
+pub struct Attribute{
+    pub attribute_type: Option<String>,
+    pub variable_name: Option<String>,
+    pub total: Option<f64>,
+    pub value: Option<f64>,
+    pub hit_dice_size: Option<String>,
+    pub spell_slot_level: Option<Calculation>,
+}
+impl Attribute{
+    ///the total rounded down, the way 5e rounds fractions
+    pub fn total_i64(&self)->Option<i64>{
+        self.total.map(|num| num.floor() as i64)
+    }
+    ///the current value rounded down, the way 5e rounds fractions
+    pub fn value_i64(&self)->Option<i64>{
+        self.value.map(|num| num.floor() as i64)
+    }
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default,rename_all="camelCase")]
+pub struct SkillProp{
+    pub skill_type: Option<String>,
+    pub value: Option<f64>,
+    pub proficiency: Option<f64>,
+    pub passive_bonus: Option<i64>,
+}
+impl SkillProp{
+    ///the modifier rounded down, the way 5e rounds fractions
+    pub fn value_i64(&self)->Option<i64>{
+        self.value.map(|num| num.floor() as i64)
+    }
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default,rename_all="camelCase")]
+pub struct ActionProp{
+    pub action_type: Option<String>,
+    pub attack_roll: Option<Calculation>,
+    pub uses: Option<Calculation>,
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default,rename_all="camelCase")]
+pub struct DamageProp{
+    pub amount: Option<Calculation>,
+    pub damage_type: Option<String>,
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default,rename_all="camelCase")]
+pub struct SpellProp{
+    pub level: Option<i64>,
+    pub action_type: Option<String>,
+    pub casting_time: Option<String>,
+    pub duration: Option<String>,
+    pub school: Option<String>,
+    pub range: Option<String>,
+    pub verbal: Option<bool>,
+    pub somatic: Option<bool>,
+    pub concentration: Option<bool>,
+    pub ritual: Option<bool>,
+    pub material: Option<String>,
+    pub always_prepared: Option<bool>,
+    pub prepared: Option<bool>,
+    pub summary: Option<InlineText>,
+    pub description: Option<InlineText>,
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default,rename_all="camelCase")]
+pub struct SpellListProp{
+    pub max_prepared: Option<Calculation>,
+    pub dc: Option<Calculation>,
+    pub attack_roll_bonus: Option<Calculation>,
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default,rename_all="camelCase")]
+pub struct ItemProp{
+    pub quantity: Option<i64>,
+    pub plural: Option<String>,
+    pub requires_attunement: Option<bool>,
+    pub attuned: Option<bool>,
+    pub equipped: Option<bool>,
+    ///in pounds, for one of the item
+    pub weight: Option<f64>,
+    ///in gold pieces, for one of the item
+    pub value: Option<f64>,
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default,rename_all="camelCase")]
+pub struct ContainerProp{
+    pub carried: Option<bool>,
+    pub contents_weightless: Option<bool>,
+    pub weight: Option<f64>,
+    pub value: Option<f64>,
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default)]
+pub struct FeatureProp{
+    pub summary: Option<InlineText>,
+    pub description: Option<InlineText>,
+    pub uses: Option<Calculation>,
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default)]
+pub struct NoteProp{
+    pub summary: Option<InlineText>,
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default,rename_all="camelCase")]
+pub struct ConstantProp{
+    pub variable_name: Option<String>,
+    pub calculation: Option<String>,
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default)]
+pub struct ClassProp{
+    pub level: Option<i64>,
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default,rename_all="camelCase")]
+pub struct DamageMultiplierProp{
+    pub value: Option<f64>,
+    pub damage_types: Vec<String>,
+}
+#[derive(Deserialize,Debug,Clone,PartialEq,Default)]
+#[serde(default)]
+pub struct SavingThrowProp{
+    pub stat: Option<String>,
+    pub dc: Option<Calculation>,
+}
@@ -0,0 +1,1160 @@
+//! Lays out the pdf character sheet: the details and three columns of the first page, the
+//! equipment and background page, then a page of spells for each spell list. `render_party` puts several
+//! characters in one document after an overview page
+use genpdf::{Element, Alignment};
+use genpdf::{elements::{self,Paragraph},fonts,style};
+use genpdf::error::Error;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use crate::holding_structs::*;
+use crate::{bns_translator,try_generate_pdf};
+
+///the ability scores and saves in the order the sheets print them
+pub(crate) const ABILITIES: [&str;6] = ["Strength","Dexterity","Constitution","Intelligence","Wisdom","Charisma"];
+///how many attacks fit on the sheet
+pub const ATTACK_ROWS: usize = 23;
+///the actions that reset uses at dawn, dusk or midnight, which are left off the sheet
+pub(crate) static PASS_TIME: LazyLock<regex::Regex> = LazyLock::new(|| regex::Regex::new(r"Pass (Dawn|Dusk|Midnight)").unwrap());
+
+///What to put on the sheet besides the character itself
+#[derive(Debug,Clone,Default)]
+pub struct SheetOptions{
+    ///print the xp, instead of leaving the box blank to be penciled in
+    pub include_xp: bool,
+    ///the attacks to print, by default all of them. At most `ATTACK_ROWS` fit on the sheet
+    pub attacks: Option<Vec<Attack>>,
+    ///the character portrait, the box is left blank without one
+    pub portrait: Option<image::DynamicImage>,
+    ///add pages with the full text of every spell after the spell lists
+    pub spell_descriptions: bool,
+    ///add pages with the full text of every feature and trait, grouped by where they come from, instead of
+    ///listing their names with the actions
+    pub feature_descriptions: bool,
+    ///show the encumbered and heavily encumbered thresholds of the variant encumbrance rule under the coins
+    pub variant_encumbrance: bool,
+}
+/// builds the character sheet, ready to be rendered with `render_to_file` or `render`.
+/// Needs the fonts in ./fonts and the images in ./images, like `generate_pdf`
+/// #Example
+/// ```no_run
+/// use dicecloud_sheet_printer::holding_structs::Character;
+/// use dicecloud_sheet_printer::sheet::{render_sheet, SheetOptions};
+///
+/// let char_json = serde_json::from_str(&std::fs::read_to_string("creature.json").unwrap()).unwrap();
+/// let race_decoder = serde_json::from_str(&std::fs::read_to_string("race_decoder.json").unwrap()).unwrap();
+/// let character = Character::try_from_json(char_json, race_decoder).unwrap();
+/// let options = SheetOptions{include_xp: true, ..Default::default()};
+/// render_sheet(&character, &options).unwrap().render_to_file("sheet.pdf").unwrap();
+/// ```
+/// #Errors
+/// fails if a font or image can't be loaded, or part of the layout could not be built
+pub fn render_sheet(character: &Character, options: &SheetOptions)->Result<genpdf::Document,Error>{
+    let mut doc = try_generate_pdf()?;
+    let symbol = symbol_style(&mut doc)?;
+    push_sheet(&mut doc, symbol, character, options)?;
+    Ok(doc)
+}
+/// builds one document for a whole party: an overview page listing every character, then each
+/// character's sheet starting on a new page, in the order given
+/// #Example
+/// ```no_run
+/// use dicecloud_sheet_printer::holding_structs::Character;
+/// use dicecloud_sheet_printer::sheet::{render_party, SheetOptions};
+///
+/// # let (fighter, wizard) = (Character::default(), Character::default());
+/// let options = SheetOptions::default();
+/// render_party(&[(&fighter, &options), (&wizard, &options)]).unwrap().render_to_file("party.pdf").unwrap();
+/// ```
+/// #Errors
+/// fails if a font or image can't be loaded, or part of the layout could not be built
+pub fn render_party(sheets: &[(&Character, &SheetOptions)])->Result<genpdf::Document,Error>{
+    let mut doc = try_generate_pdf()?;
+    doc.set_title("Party Character Sheets");
+    let symbol = symbol_style(&mut doc)?;
+    let characters: Vec<&Character> = sheets.iter().map(|(character, _)| *character).collect();
+    doc.push(party_overview(&characters)?);
+    for (character, options) in sheets{
+        doc.push(elements::PageBreak::new());
+        push_sheet(&mut doc, symbol, character, options)?;
+    }
+    Ok(doc)
+}
+/// builds a one page summary of the party for the game master, on a landscape page: armor class, hit points,
+/// passive perception, speed, initiative, spell save dcs, damage multipliers, languages and notable proficiencies
+/// of each character in one table
+/// #Example
+/// ```no_run
+/// use dicecloud_sheet_printer::holding_structs::Character;
+/// use dicecloud_sheet_printer::sheet::render_party_summary;
+///
+/// # let (fighter, wizard) = (Character::default(), Character::default());
+/// render_party_summary(&[&fighter, &wizard]).unwrap().render_to_file("party_summary.pdf").unwrap();
+/// ```
+/// #Errors
+/// fails if the font can't be loaded
+pub fn render_party_summary(characters: &[&Character])->Result<genpdf::Document,Error>{
+    let mut doc = try_generate_pdf()?;
+    doc.set_title("Party Summary");
+    doc.set_paper_size(genpdf::Size::new(297, 210));
+    let label = style::Style::new().bold().with_font_size(7);
+    let txt = style::Style::new().with_font_size(8);
+    let mut table = elements::TableLayout::new(vec![6,2,2,2,2,2,5,6,5,8]);
+    table.set_cell_decorator(elements::FrameCellDecorator::new(true, true, false));
+    let mut header = table.row();
+    for col in ["NAME","AC","MAX HP","PASSIVE PERC.","SPEED","INIT.","SPELL SAVE DC","DAMAGE","LANGUAGES","NOTABLE PROFICIENCIES"]{
+        header.push_element(Paragraph::new(col).styled(label).padded(1));
+    }
+    header.push()?;
+    for character in characters{
+        let spell_dcs: Vec<String> = character.spell_lists.iter().map(|ls| format!("{} DC {}",ls.name,ls.save_dc)).collect();
+        table.row()
+            .element(elements::LinearLayout::vertical()
+                .element(Paragraph::new(&character.char_name).styled(style::Style::new().bold().with_font_size(8)))
+                .element(Paragraph::new(format!("{} {}",character.race,character.level())).styled(style::Style::new().with_font_size(7)))
+                .padded(1))
+            .element(Paragraph::new(character.ac.to_string()).aligned(Alignment::Center).styled(txt).padded(1))
+            .element(Paragraph::new(character.hit_points.to_string()).aligned(Alignment::Center).styled(txt).padded(1))
+            .element(Paragraph::new(character.passive_perception().to_string()).aligned(Alignment::Center).styled(txt).padded(1))
+            .element(Paragraph::new(format!("{} ft",character.speed)).aligned(Alignment::Center).styled(txt).padded(1))
+            .element(Paragraph::new(bns_translator(character.initiative)).aligned(Alignment::Center).styled(txt).padded(1))
+            .element(lines(spell_dcs, txt).padded(1))
+            .element(lines(damage_summary(&character.damage_mults), txt).padded(1))
+            .element(Paragraph::new(character.other_profs.2.join(", ")).styled(txt).padded(1))
+            .element(lines(notable_proficiencies(character), txt).padded(1))
+            .push()?;
+    }
+    doc.push(Paragraph::new("PARTY SUMMARY").styled(style::Style::new().bold().with_font_size(14)));
+    doc.push(elements::Break::new(1.0));
+    doc.push(table);
+    Ok(doc)
+}
+/// one paragraph per line, a dash if there are none
+fn lines(lines: Vec<String>, txt: style::Style)->elements::LinearLayout{
+    let mut layout = elements::LinearLayout::vertical();
+    if lines.is_empty(){
+        layout.push(Paragraph::new("-").styled(txt));
+    }
+    for line in lines{
+        layout.push(Paragraph::new(line).styled(txt));
+    }
+    layout
+}
+/// the damage types a character is immune, resistant and vulnerable to, one line each
+fn damage_summary(dmg_mults: &[DamageMult])->Vec<String>{
+    let mut dmg_mults = dmg_mults.to_vec();
+    dmg_mults.sort();
+    dmg_mults.dedup();
+    let mut by_kind: Vec<(&str,Vec<&str>)> = vec![("Immune",vec![]),("Resist",vec![]),("Vulnerable",vec![])];
+    for mult in &dmg_mults{
+        let (i, typ) = match mult{
+            DamageMult::Immune(typ)=>(0, typ),
+            DamageMult::Resist(typ)=>(1, typ),
+            DamageMult::Vuln(typ)=>(2, typ),
+        };
+        by_kind[i].1.push(typ);
+    }
+    by_kind.into_iter().filter(|(_, types)| !types.is_empty())
+        .map(|(kind, types)| format!("{}: {}",kind,types.join(", "))).collect()
+}
+/// the proficient saving throws, skills with expertise and armor proficiencies
+fn notable_proficiencies(character: &Character)->Vec<String>{
+    let mut profs = Vec::new();
+    let saves: Vec<String> = character.saving_throws.iter().filter(|save| *save.prof()!=Proficiency::None)
+        .map(|save| save.name().replace(" Save","").chars().take(3).collect()).collect();
+    if !saves.is_empty(){
+        profs.push(format!("Saves: {}",saves.join(", ")));
+    }
+    let expertise: Vec<&str> = character.skills.iter().filter(|skill| *skill.prof()==Proficiency::Expert)
+        .map(|skill| skill.name().as_str()).collect();
+    if !expertise.is_empty(){
+        profs.push(format!("Expertise: {}",expertise.join(", ")));
+    }
+    if !character.other_profs.0.is_empty(){
+        profs.push(format!("Armor: {}",character.other_profs.0.join(", ")));
+    }
+    profs
+}
+/// adds the symbol font used for proficiency and preparation markers
+fn symbol_style(doc: &mut genpdf::Document)->Result<style::Style,Error>{
+    let symbol_font = doc.add_font_family(fonts::from_files("./fonts/Noto_Sans_Symbols_2","NotoSansSymbols2",None)?);
+    Ok(style::Style::from(symbol_font))
+}
+/// the table of contents of a party document: name, race, classes and level of each character
+fn party_overview(characters: &[&Character])->Result<elements::LinearLayout,Error>{
+    let label = style::Style::new().bold().with_font_size(7);
+    let mut table = elements::TableLayout::new(vec![1,8,6,10,2]);
+    table.set_cell_decorator(elements::FrameCellDecorator::new(true, true, false));
+    table.row()
+        .element(Paragraph::new("#").styled(label).padded(1))
+        .element(Paragraph::new("NAME").styled(label).padded(1))
+        .element(Paragraph::new("RACE").styled(label).padded(1))
+        .element(Paragraph::new("CLASSES").styled(label).padded(1))
+        .element(Paragraph::new("LEVEL").styled(label).padded(1))
+        .push()?;
+    for (i, character) in characters.iter().enumerate(){
+        let mut classes = character.classes.clone();
+        classes.sort();
+        let class_str = classes.iter().map(|class| format!("{} {}",class.name(),class.level())).collect::<Vec<_>>().join(" / ");
+        table.row()
+            .element(Paragraph::new((i+1).to_string()).padded(1))
+            .element(Paragraph::new(&character.char_name).padded(1))
+            .element(Paragraph::new(&character.race).padded(1))
+            .element(Paragraph::new(class_str).padded(1))
+            .element(Paragraph::new(character.level().to_string()).aligned(Alignment::Center).padded(1))
+            .push()?;
+    }
+    Ok(elements::LinearLayout::vertical()
+        .element(Paragraph::new("PARTY").styled(style::Style::new().bold().with_font_size(14)))
+        .element(Paragraph::new(format!("{} characters, each sheet follows in this order",characters.len()))
+            .styled(style::Style::new().with_font_size(8)))
+        .element(elements::Break::new(1.0))
+        .element(table))
+}
+/// lays out the sheet of one character at the end of `doc`
+fn push_sheet(doc: &mut genpdf::Document, symbol: style::Style, character: &Character, options: &SheetOptions)->Result<(),Error>{
+    let mut header = elements::TableLayout::new(vec![2,15]);
+    
+    header
+        .row()
+        .element(elements::Image::from_path("./images/dicecloud_favicon.jpg")?
+            .with_scale(genpdf::Scale::new(2,2))
+            .with_position(genpdf::Position::new(13,1))
+            )
+        .element(Paragraph::new("DUNGEONS AND DRAGONS")
+            .aligned(Alignment::Left)
+            .styled(style::Style::new().bold().with_font_size(11)))
+        .push()?;
+    doc.push(header);
+    let mut detail = elements::TableLayout::new(vec![1,2]);
+    let passive_bonus = character.passive_perception();
+    detail.set_cell_decorator(elements::FrameCellDecorator::new(false, false, false));
+    let detail_left = elements::LinearLayout::vertical()
+        .element(elements::Break::new(1.0))
+        .element(Paragraph::new(&character.char_name)
+            .aligned(Alignment::Center)
+            .framed());
+    let mut xp = String::new();
+    if character.xp>0 && options.include_xp{
+        xp+=&character.xp.to_string();
+    }
+    let mut detail_right = elements::TableLayout::new(vec![2,2,1]);
+    detail_right.set_cell_decorator(elements::FrameCellDecorator::new(false, false, false));
+    let mut classes = character.classes.clone();
+    classes.sort();
+    let class_str: String = if classes.len()==1{
+        classes.iter().map(|class| format!("{} {}",class.name(),class.level())).collect::<Vec<String>>().join(" ")
+    } else {
+        classes.iter().map(|class| format!("{}. {}",class.name().chars().take(3).collect::<String>(),class.level())).collect::<Vec<String>>().join(" ")
+    };
+    detail_right
+        .row()
+        .element(
+            Paragraph::new(class_str).styled(style::Style::new().with_line_spacing(0.5))
+        )
+        .element(
+            Paragraph::new(character.background.name()).styled(style::Style::new().with_line_spacing(0.5))
+        )
+        .element(
+            Paragraph::new("").styled(style::Style::new().with_line_spacing(0.5))
+        )
+        .push()?;
+    detail_right
+        .row()
+        .element(
+            Paragraph::new("Class").styled(style::Style::new().with_font_size(7))
+        )
+        .element(
+            Paragraph::new("Background").styled(style::Style::new().with_font_size(7))
+        )
+        .element(
+            Paragraph::new("Player Name").styled(style::Style::new().with_font_size(7))
+        )
+        .push()?;
+    detail_right
+        .row()
+        .element(
+            Paragraph::new(&character.race).styled(style::Style::new().with_line_spacing(0.5))
+        )
+        .element(
+            Paragraph::new(&character.alignment).styled(style::Style::new().with_line_spacing(0.5))
+        )
+        .element(
+            Paragraph::new(xp).styled(style::Style::new().with_line_spacing(0.5))
+        )
+        .push()?;
+    detail_right
+        .row()
+        .element(
+            Paragraph::new("Race")
+                .styled(style::Style::new().with_font_size(7))
+        )
+        .element(
+            Paragraph::new("Alignment")
+                .styled(style::Style::new().with_font_size(7))
+        )
+        .element(
+            Paragraph::new("Experience Points")
+                .styled(style::Style::new().with_font_size(7))
+        )
+        .push()?;
+    detail
+        .row()
+        .element(
+            detail_left.padded(1)
+        )
+        .element(
+            detail_right
+                .padded(1).framed()
+        )
+        .push()?;
+    doc.push(detail);
+    doc.push(elements::Break::new(1.0));
+    let mut main_sheet = elements::TableLayout::new(vec![1,1,1]);
+    let mut ability_scores: HashMap<&str,&AbilityScore> = HashMap::new();
+    for score in &character.ability_scores{
+        ability_scores.insert(score.name(),score);
+    }
+    main_sheet.set_cell_decorator(elements::FrameCellDecorator::new(false, false, false));    
+    let mut left_bar = elements::TableLayout::new(vec![1,2]);
+    left_bar.set_cell_decorator(elements::FrameCellDecorator::new(false, false, false));
+    let mut skills = character.skills.clone();
+    let mut saves: HashMap<String,&Skill> = HashMap::new();
+    for save in &character.saving_throws{
+        let nme = save.name().replace(" Save","");
+        saves.insert(nme,save);
+    }
+    let mut saving_throws = elements::LinearLayout::vertical();
+    for name in ABILITIES{
+        saving_throws = match saves.get(name){
+            Some(save)=>saving_throws.element(element_from_skill(save,&symbol)),
+            //a lenient or hand built character can be missing a save, leave it blank
+            None=>saving_throws.element(Paragraph::default()
+                .styled_string(proficiency_translator(&Proficiency::None),symbol)
+                .string(format!("      {} Save",name))
+                .styled(style::Style::new().with_font_size(8))),
+        };
+    }
+    let saving_throws = saving_throws
+        .element(elements::Break::new(0.5))
+        .element(Paragraph::new("SAVING THROWS")
+            .aligned(Alignment::Center)
+            .styled(style::Style::new().bold().with_font_size(7))
+        );
+    let mut skill_element = elements::LinearLayout::vertical();
+    
+    skills.sort();
+    for skill in skills{
+        skill_element=skill_element.element(element_from_skill(&skill,&symbol));
+    }
+    skill_element=skill_element.element(elements::Break::new(0.5))
+        .element(
+            Paragraph::new("SKILLS")
+                .aligned(Alignment::Center)
+                .styled(style::Style::new().bold().with_font_size(8))
+        );
+    let mut inspiration = elements::TableLayout::new(vec![2,9]);
+    inspiration.set_cell_decorator(elements::FrameCellDecorator::new(true, true, false));
+    inspiration.row()
+        .element(Paragraph::new(""))
+        .element(Paragraph::new("INSPIRATION")
+            .aligned(Alignment::Center)
+            .styled(style::Style::new().bold().with_font_size(7))
+            .padded(2)
+        ).push()?;
+    let mut prof_bonus = elements::TableLayout::new(vec![2,9]);
+    prof_bonus.set_cell_decorator(elements::FrameCellDecorator::new(true,true,false));
+    prof_bonus.row()
+        .element(Paragraph::new(bns_translator(character.prof_bonus))
+            .aligned(Alignment::Center))
+        .element(Paragraph::new("PROFICIENCY BONUS")
+            .aligned(Alignment::Center)
+            .styled(style::Style::new().bold().with_font_size(7))
+            .padded(2)
+    ).push()?;
+    let mut score_boxes = elements::LinearLayout::vertical();
+    for (idx,name) in ABILITIES.into_iter().enumerate(){
+        score_boxes = score_boxes
+            .element(elements::Break::new(if idx==0 {0.5} else {1.25}))
+            .element(element_from_score(name,ability_scores.get(name).copied()).framed());
+    }
+    left_bar
+        .row()
+        .element(score_boxes.padded(1))
+        .element(elements::LinearLayout::vertical()
+            .element(inspiration)
+            .element(elements::Break::new(0.75))
+            .element(prof_bonus)
+            .element(elements::Break::new(0.75))
+            .element(saving_throws.padded(3).framed())
+            .element(elements::Break::new(0.75))
+            .element(skill_element.padded(3).framed())
+            .padded(1)
+        )
+        .push()?;
+    let other_profs = (character.other_profs.0.join(", "),
+        character.other_profs.1.join(", "),character.other_profs.2.join(", "),
+        character.other_profs.3.join(", "));
+    
+    let mut passive_perception = elements::TableLayout::new(vec![2,9]);
+    passive_perception.set_cell_decorator(elements::FrameCellDecorator::new(true,true,false));
+    passive_perception
+        .row()
+        .element(Paragraph::new(passive_bonus.to_string())
+            .aligned(Alignment::Center))
+        .element(Paragraph::new("PASSIVE PERCEPTION")
+            .aligned(Alignment::Center)
+            .styled(style::Style::new().with_font_size(7))
+            .padded(2)
+        )
+        .push()?;
+    let mut middle_column = elements::LinearLayout::vertical();
+    let mut top_middle= elements::TableLayout::new(vec![1,1,1]);
+    top_middle.set_cell_decorator(elements::FrameCellDecorator::new(false,false,false));
+    top_middle.row()
+        .element(
+            elements::LinearLayout::vertical()
+                .element(
+                    Paragraph::new(character.ac.to_string())
+                        .aligned(Alignment::Center)
+                )
+                .element(
+                    Paragraph::new("ARMOR CLASS")
+                        .aligned(Alignment::Center)
+                        .styled(style::Style::new().bold().with_font_size(7))
+                )
+                .padded(2)
+                .framed()
+                .padded(1)
+        )
+        .element(
+            elements::LinearLayout::vertical()
+                .element(elements::Break::new(0.5))
+                .element(
+                    Paragraph::new(bns_translator(character.initiative))
+                        .aligned(Alignment::Center)
+                )
+                .element(
+                    Paragraph::new("INITIATIVE")
+                        .aligned(Alignment::Center)
+                        .styled(style::Style::new().bold().with_font_size(7))
+                )
+                .element(elements::Break::new(0.4))
+                .padded(1)
+                .framed()
+                .padded(1)
+        )
+        .element(
+            elements::LinearLayout::vertical()
+                .element(elements::Break::new(0.5))
+                .element(
+                    Paragraph::new(character.speed.to_string())
+                        .aligned(Alignment::Center)
+                )
+                .element(
+                    Paragraph::new("SPEED")
+                        .aligned(Alignment::Center)
+                        .styled(style::Style::new().bold().with_font_size(7))
+                )
+                .element(elements::Break::new(0.4))
+                .padded(1)
+                .framed()
+                .padded(1)
+            )
+        .push()?;
+    middle_column=middle_column.element(top_middle);
+    let hit_point_detail=elements::LinearLayout::vertical()
+            .element(Paragraph::new(format!("Hit Point Maximum: {}",character.hit_points))
+                .styled(style::Style::new().with_font_size(7))
+                .padded(2)
+                .framed())
+            .element(elements::Break::new(2.25))
+            .element(Paragraph::new("CURRENT HIT POINTS")
+                .aligned(Alignment::Center)
+                .styled(style::Style::new().bold().with_font_size(7)));
+    middle_column=middle_column.element(elements::Break::new(0.25))
+            .element(hit_point_detail.framed().padded(2))
+            .element(elements::Break::new(0.1))
+            .element(elements::LinearLayout::vertical()
+                .element(elements::Break::new(2.25))
+                .element(Paragraph::new("TEMPORARY HIT POINTS")
+                    .aligned(Alignment::Center)
+                    .styled(style::Style::new().bold().with_font_size(7))
+                )
+                .framed()
+                .padded(2)
+            )
+            .element(elements::Break::new(0.25));
+    let mut mid_tbl = elements::TableLayout::new(vec![1,1]);
+    mid_tbl.set_cell_decorator(elements::FrameCellDecorator::new(false,false,false));
+    let hd_str: String = character.hit_dice.iter().map(|die| die.to_string()).collect::<Vec<_>>().join(", ");
+    mid_tbl.row()
+        .element(elements::LinearLayout::vertical()
+            .element(Paragraph::new(format!("Total: {}",hd_str))
+                .styled(style::Style::new().with_font_size(7))
+            )
+            .element(elements::Break::new(1))
+            .element(Paragraph::new("HIT DICE")
+                .aligned(Alignment::Center)
+                .styled(style::Style::new().with_font_size(7))
+            )
+            .padded(1)
+            .framed()
+            .padded(1)
+        )
+        .element(elements::LinearLayout::vertical()
+            .element(Paragraph::default()
+                .styled_string("SUCCESSES ",style::Style::new().with_font_size(7))
+                .styled_string("⭘",symbol.with_font_size(7))
+                .styled_string("-",style::Style::new().with_font_size(7))
+                .styled_string("⭘",symbol.with_font_size(7))
+                .styled_string("-",style::Style::new().with_font_size(7))
+                .styled_string("⭘ ",symbol.with_font_size(7))
+                .aligned(Alignment::Right)
+            )
+            .element(Paragraph::default()
+                .styled_string("FAILURES ",style::Style::new().with_font_size(7))
+                .styled_string("⭘",symbol.with_font_size(7))
+                .styled_string("-",style::Style::new().with_font_size(7))
+                .styled_string("⭘",symbol.with_font_size(7))
+                .styled_string("-",style::Style::new().with_font_size(7))
+                .styled_string("⭘ ",symbol.with_font_size(7))
+                .aligned(Alignment::Right)
+            )
+            .element(Paragraph::new("DEATH SAVES")
+                .aligned(Alignment::Center)
+                .styled(style::Style::new().with_font_size(7)))
+            .framed()
+            .padded(1)
+        )
+        .push()?;
+    middle_column=middle_column.element(mid_tbl);
+    let mut to_display = options.attacks.clone().unwrap_or_else(|| character.attacks.clone());
+    let num_atks = to_display.len();
+    to_display.sort();
+    let mut attack_display=elements::TableLayout::new(vec![2,1,2]);
+    attack_display.row()
+        .element(Paragraph::new("NAME").styled(style::Style::new().with_font_size(7)))
+        .element(Paragraph::new("ATK BONUS").styled(style::Style::new().with_font_size(7)))
+        .element(Paragraph::new("DAMAGE/TYPE").styled(style::Style::new().with_font_size(7)))
+        .push()?;
+    for atk in to_display{
+        let nme = atk.name().to_string();
+        let mut trunk = nme.replace(" (Two-Handed)","(2H)");
+        trunk.truncate(13);
+        attack_display.row()
+            .element(Paragraph::new(trunk)
+                .styled(style::Style::new().bold().with_font_size(10))
+                )
+            .element(
+                Paragraph::new(atk.bonus_as_string())
+                .aligned(Alignment::Center)
+                .styled(style::Style::new().with_font_size(10))
+            )
+            .element(
+                Paragraph::new(atk.damage())
+                .styled(style::Style::new().with_font_size(10))
+            )
+            .push()?;
+    }
+    if num_atks<ATTACK_ROWS{
+        let needed=ATTACK_ROWS-num_atks;
+        for _ in 0..needed{
+            attack_display.row()
+                .element(Paragraph::new("")
+                    .styled(style::Style::new().bold().with_font_size(10))
+                )
+                .element(
+                    Paragraph::new("")
+                    .aligned(Alignment::Center)
+                    .styled(style::Style::new().with_font_size(10))
+                )
+                .element(
+                    Paragraph::new("")
+                    .styled(style::Style::new().with_font_size(10))
+                )
+            .push()?;
+        }
+    }
+    middle_column=middle_column.element(attack_display.padded(1).framed().padded(1));
+    let traits = character.traits.clone();
+    let personality = elements::LinearLayout::vertical()
+        .element(vertical_pad(traits.0,28,3))
+        .element(Paragraph::new("PERSONALITY TRAITS")
+            .aligned(Alignment::Center)
+            .styled(style::Style::new().with_font_size(7).bold()))
+        .padded(1)
+        .framed()
+        .padded(1);
+    let ideal = elements::LinearLayout::vertical()
+        .element(vertical_pad(traits.1,28,2))
+        .element(Paragraph::new("IDEALS")
+            .aligned(Alignment::Center)
+            .styled(style::Style::new().with_font_size(7).bold()))
+        .padded(1)
+        .framed()
+        .padded(1);
+    let bond = elements::LinearLayout::vertical()
+        .element(vertical_pad(traits.2,28,2))
+        .element(Paragraph::new("BONDS")
+            .aligned(Alignment::Center)
+            .styled(style::Style::new().with_font_size(7).bold()))
+        .padded(1)
+        .framed()
+        .padded(1);
+    let flaw = elements::LinearLayout::vertical()
+        .element(vertical_pad(traits.3,28,2))
+        .element(Paragraph::new("FLAWS")
+            .aligned(Alignment::Center)
+            .styled(style::Style::new().with_font_size(7).bold()))
+        .padded(1)
+        .framed()
+        .padded(1);
+    let traits_elemt= elements::LinearLayout::vertical()
+        .element(personality)
+        .element(elements::Break::new(0.5))
+        .element(ideal)
+        .element(elements::Break::new(0.5))
+        .element(bond)
+        .element(elements::Break::new(0.5))
+        .element(flaw)
+        .element(elements::Break::new(0.25));
+    let mut features_elem= elements::LinearLayout::vertical();
+    features_elem=features_elem.element(Paragraph::new("ACTIONS").aligned(Alignment::Center)
+        .styled(style::Style::new().bold().with_font_size(7)));
+    let mut actions = character.actions.clone();
+    let mut equipment = character.equipment.clone();
+    equipment.sort();
+    actions.sort();
+    actions.push(Action::default());
+    //printed in full at the end instead
+    let mut features = if options.feature_descriptions {Vec::new()} else {character.features.clone()};
+    let mut dmg_mults = character.damage_mults.clone();
+    dmg_mults.sort();
+    features.sort();
+    let mut resources = character.resources.clone();
+    resources.sort();
+    let mut features = resources.into_iter().map(|r| r.to_string()).chain(dmg_mults.into_iter().map(|mul| mul.to_string()))
+        .chain(features.into_iter().filter(|feat| !actions.iter().any(|x| feat.name()==x.name())).map(|feat| feat.to_string()));
+    let mut actions_itr= actions.iter().filter(|act| !PASS_TIME.is_match(act.name()))
+        .filter(|act| !equipment.iter().any(|x| act.name()==x.name() && act.uses() !=-1))
+        .map(|act| act.to_string());
+    for _i in 0..26{
+        if let Some(name)=actions_itr.next(){
+            if name==Action::default().to_string(){
+                features_elem=features_elem.element(Hline::new());
+                features_elem=features_elem.element(Paragraph::new("FEATURES").aligned(Alignment::Center)
+                    .styled(style::Style::new().bold().with_font_size(7)))
+            } else {
+                features_elem=features_elem.element(Paragraph::new(name).aligned(Alignment::Center)
+                    .styled(style::Style::new().with_font_size(10)));
+            }
+        } else if let Some(name)=features.next(){
+            features_elem=features_elem.element(Paragraph::new(name).aligned(Alignment::Center)
+                .styled(style::Style::new().with_font_size(10)));
+        }else {
+            features_elem=features_elem.element(elements::Break::new(1.0).styled(style::Style::new().with_font_size(10)));
+        }
+
+    }
+    main_sheet
+        .row()
+        .element(elements::LinearLayout::vertical()
+            .element(left_bar)
+            .element(elements::Break::new(0.5))
+            .element(passive_perception.padded(1))
+            .element(elements::Break::new(1.0))
+            .element(elements::LinearLayout::vertical()
+                .element(Paragraph::default()
+                    .styled_string("Armor: ",style::Style::new().bold().with_font_size(7))
+                    .styled_string(other_profs.0,style::Style::new().with_font_size(7))
+                    .aligned(Alignment::Center)
+                    .padded(1)
+                )
+                .element(Paragraph::default()
+                    .styled_string("Weapons: ",style::Style::new().bold().with_font_size(7))
+                    .styled_string(other_profs.1,style::Style::new().with_font_size(7))
+                    .aligned(Alignment::Center)
+                    .padded(1)
+                )
+                .element(Paragraph::default()
+                    .styled_string("Languages: ",style::Style::new().bold().with_font_size(7))
+                    .styled_string(other_profs.2,style::Style::new().with_font_size(7))
+                    .aligned(Alignment::Center)
+                    .padded(1)
+                )
+                .element(Paragraph::default()
+                    .styled_string("Tools: ",style::Style::new().bold().with_font_size(7))
+                    .styled_string(other_profs.3,style::Style::new().with_font_size(7))
+                    .aligned(Alignment::Center)
+                    .padded(1)
+                )
+                .element(Paragraph::new("OTHER PROFICIENCIES & LANGUAGES")
+                    .aligned(Alignment::Center)
+                    .styled(style::Style::new().bold().with_font_size(7)))
+                .framed()
+                .padded(1)
+            )
+        )
+        .element(elements::LinearLayout::vertical()
+            .element(elements::Break::new(0.25))
+            .element(middle_column)
+        )
+        .element(elements::LinearLayout::vertical()
+            .element(traits_elemt)
+            .element(elements::Break::new(0.25))
+            .element(features_elem.padded(1).framed().padded(1))
+            .element(elements::PageBreak::new())
+        )
+        .push()?;
+    doc.push(main_sheet);
+    
+    // page 2 starts
+    let mut page_2 = elements::TableLayout::new(vec![3,1]);
+    let mut equipment_elem = elements::LinearLayout::vertical()
+        .element(Paragraph::new("EQUIPMENT").aligned(Alignment::Center)
+            .styled(style::Style::new().bold().with_font_size(7)));
+    for (container, items) in equipment_groups(character){
+        if let Some(cnt)=container{
+            let inside = character.container(cnt.container()).map(|outer| format!(" (in {})",outer.name())).unwrap_or_default();
+            let weightless = if cnt.contents_weightless() {", weightless"} else {""};
+            equipment_elem = equipment_elem.element(Paragraph::default()
+                .styled_string(format!("{}{}",cnt.name().to_uppercase(),inside),style::Style::new().bold().with_font_size(7))
+                .string(format!("  {}{}, {}",weight_text(character.contents_weight(cnt.id())),weightless,value_text(character.contents_value(cnt.id()))))
+                .aligned(Alignment::Center)
+                .styled(style::Style::new().with_font_size(7)));
+        }
+        for itm in items{
+            let q = itm.quantity();
+            let nme = if q==1{
+                itm.name()
+            } else {
+                itm.plural_name()
+            };
+            equipment_elem = equipment_elem.element(Paragraph::default().styled_string(if itm.requires_attunement() {"❂ "} else {""},symbol)
+                    .string(format!("{}{}",if q==1 {"".to_string()} else {q.to_string()+" "},nme))
+                    .styled_string(item_status(itm),style::Style::new().italic().with_font_size(8))
+                    .aligned(Alignment::Center)
+                    .styled(style::Style::new().with_font_size(10))
+                );
+        }
+    }
+    if !character.equipment.is_empty() || !character.containers.is_empty(){
+        equipment_elem = equipment_elem.element(Hline::new())
+            .element(Paragraph::new(format!("TOTAL  {}, {}",weight_text(character.contents_weight("")),value_text(character.contents_value(""))))
+                .aligned(Alignment::Center)
+                .styled(style::Style::new().bold().with_font_size(7)));
+    }
+    let mut features_elem2= elements::LinearLayout::vertical();
+    features_elem2=features_elem2.element(Paragraph::new("OTHER FEATURES & TRAITS").aligned(Alignment::Center)
+        .styled(style::Style::new().bold().with_font_size(7)));
+    for name in actions_itr{
+        if name==String::default(){
+            features_elem2=features_elem2.element(Hline::new());
+            features_elem2=features_elem2.element(Paragraph::new("FEATURES").aligned(Alignment::Center)
+                .styled(style::Style::new().bold().with_font_size(7)))
+        } else {
+            features_elem2=features_elem2.element(Paragraph::new(name).aligned(Alignment::Center)
+                .styled(style::Style::new().with_font_size(10)));
+        }
+    }
+    for name in features{
+        features_elem2=features_elem2.element(Paragraph::new(name).aligned(Alignment::Center)
+            .styled(style::Style::new().with_font_size(10)));
+    }
+    let coins = character.coins;
+    let mut equiptable = elements::TableLayout::new(vec![1,9]);
+    equiptable.row()
+        .element(elements::LinearLayout::vertical()
+            .element(elements::Break::new(1.0))
+            .element(Paragraph::new("CP").aligned(Alignment::Center)
+                .styled(style::Style::new().bold().with_font_size(7)))
+            .element(Paragraph::new(coins.0.to_string()).aligned(Alignment::Center).padded(1).framed().padded(1))
+            .element(elements::Break::new(0.5))
+            .element(Paragraph::new("SP").aligned(Alignment::Center)
+                .styled(style::Style::new().bold().with_font_size(7)))
+            .element(Paragraph::new(coins.1.to_string()).aligned(Alignment::Center).padded(1).framed().padded(1))
+            .element(elements::Break::new(0.5))
+            .element(Paragraph::new("EP").aligned(Alignment::Center)
+                .styled(style::Style::new().bold().with_font_size(7)))
+            .element(Paragraph::new(coins.2.to_string()).aligned(Alignment::Center).padded(1).framed().padded(1))
+            .element(elements::Break::new(0.5))
+            .element(Paragraph::new("GP").aligned(Alignment::Center)
+                .styled(style::Style::new().bold().with_font_size(7)))
+            .element(Paragraph::new(coins.3.to_string()).aligned(Alignment::Center).padded(1).framed().padded(1))
+            .element(elements::Break::new(0.5))
+            .element(Paragraph::new("PP").aligned(Alignment::Center)
+                .styled(style::Style::new().bold().with_font_size(7)))
+            .element(Paragraph::new(coins.4.to_string()).aligned(Alignment::Center).padded(1).framed().padded(1))
+            .element(encumbrance_boxes(character, options.variant_encumbrance))
+            )
+        .element(equipment_elem.padded(1))
+        .push()?;
+    let background = &character.background;
+    let mut img_elem = elements::LinearLayout::vertical();
+    if let Some(img)=options.portrait.clone(){
+        img_elem=img_elem.element(elements::Image::from_dynamic_image(img)?
+            .with_scale(genpdf::Scale{x:0.9,y:0.9})
+            .with_alignment(Alignment::Center));
+    } else {
+        img_elem=img_elem.element(elements::Break::new(6.0));
+    }
+    img_elem=img_elem.element(Paragraph::new("CHARACTER PORTRAIT").aligned(Alignment::Center)
+        .styled(style::Style::new().bold().with_font_size(7)));
+    page_2
+        .row()
+        .element(elements::LinearLayout::vertical()
+            .element(equiptable.framed().padded(1))
+            .element(features_elem2.padded(1).framed().padded(1))
+        )
+        .element(elements::LinearLayout::vertical()
+                .element(img_elem
+                    .padded(1)
+                    .framed()
+                    .padded(1)
+                )
+                .element(elements::LinearLayout::vertical()
+                    .element(Paragraph::new("BACKGROUND").aligned(Alignment::Center)
+                        .styled(style::Style::new().bold().with_font_size(7)))
+                    .element(Paragraph::new(background.background_feature().name()).aligned(Alignment::Center)
+                        .styled(style::Style::new().bold()))
+                    .element(Paragraph::new(background.background_feature().summary()).aligned(Alignment::Center)
+                        .styled(style::Style::new().with_font_size(10)))
+                    .padded(1)
+                    .framed()
+                    .padded(1)
+                )
+        )
+        .push()?;
+    doc.push(page_2);
+    let mut spl_lists = character.spell_lists.clone();
+    let spl_slots = character.spell_slots;
+    if !spl_lists.is_empty(){
+        doc.push(elements::PageBreak::new());
+        doc.push(elements::Paragraph::new("SPELLS").styled(style::Style::new().bold()));
+        let mut spell_slots_table = elements::TableLayout::new(vec![1,1,1,1,1,1,1,1,1]);
+        let slt_fmt = style::Style::new().bold().with_font_size(7);
+        spell_slots_table.row()
+            .element(spell_slot_elem(&spl_slots, 1,symbol,slt_fmt).padded(1).framed())
+            .element(spell_slot_elem(&spl_slots, 2,symbol,slt_fmt).padded(1).framed())
+            .element(spell_slot_elem(&spl_slots, 3,symbol,slt_fmt).padded(1).framed())
+            .element(spell_slot_elem(&spl_slots, 4,symbol,slt_fmt).padded(1).framed())
+            .element(spell_slot_elem(&spl_slots, 5,symbol,slt_fmt).padded(1).framed())
+            .element(spell_slot_elem(&spl_slots, 6,symbol,slt_fmt).padded(1).framed())
+            .element(spell_slot_elem(&spl_slots, 7,symbol,slt_fmt).padded(1).framed())
+            .element(spell_slot_elem(&spl_slots, 8,symbol,slt_fmt).padded(1).framed())
+            .element(spell_slot_elem(&spl_slots, 9,symbol,slt_fmt).padded(1).framed())
+            .push()?;
+        doc.push(spell_slots_table);
+        spl_lists.sort();
+        for ls in spl_lists{
+            let mut spell_header = elements::TableLayout::new(vec![3,1,1,1]);
+            spell_header.row()
+                .element(Paragraph::new(&ls.name).aligned(Alignment::Center)
+                    .styled(style::Style::new().bold().with_font_size(14)).padded(1).framed().padded(1))
+                .element(elements::LinearLayout::vertical()
+                    .element(Paragraph::new(bns_translator(ls.atk_bonus)).aligned(Alignment::Center)
+                        .styled(style::Style::new().with_font_size(10)))
+                    .element(Paragraph::new("ATTACK BONUS").aligned(Alignment::Center)
+                        .styled(slt_fmt))
+                    .padded(1).framed().padded(1)
+                )
+                .element(elements::LinearLayout::vertical()
+                    .element(Paragraph::new(format!("DC {}",ls.save_dc)).aligned(Alignment::Center)
+                        .styled(style::Style::new().with_font_size(10)))
+                    .element(Paragraph::new("SAVE DC").aligned(Alignment::Center)
+                        .styled(slt_fmt))
+                    .padded(1).framed().padded(1)
+                )
+                .element(elements::LinearLayout::vertical()
+                    .element(Paragraph::new(format!("/{}",ls.max_prepared)).aligned(Alignment::Right)
+                        .styled(style::Style::new().with_font_size(10)))
+                    .element(Paragraph::new("PREPARED").aligned(Alignment::Center)
+                        .styled(slt_fmt))
+                    .padded(1).framed().padded(1)
+                )
+                .push()?;
+            doc.push(spell_header);
+            let mut spell_column_specifier =elements::TableLayout::new(vec![1,11,3,4,5,2,4,11]);
+            spell_column_specifier.row()
+                .element(Paragraph::new("P").styled(slt_fmt))
+                .element(Paragraph::new("NAME").styled(slt_fmt))
+                .element(Paragraph::new("SCHOOL").styled(slt_fmt))
+                .element(Paragraph::new("CAST TIME").styled(slt_fmt))
+                .element(Paragraph::new("RANGE").styled(slt_fmt))
+                .element(Paragraph::new("VSCR").styled(slt_fmt))
+                .element(Paragraph::new("DUR").styled(slt_fmt))
+                .element(Paragraph::new("MATERIAL").styled(slt_fmt))
+                .push()?;
+            doc.push(spell_column_specifier);
+            let mxlvl = ls.max_lvl();
+            for i in 0..=mxlvl{
+                if i==0{
+                    doc.push(Paragraph::new("CANTRIPS").styled(style::Style::new().bold().with_font_size(11)));
+                } else {
+                    doc.push(Paragraph::new(format!("{} LEVEL SPELLS",ordinal(i).to_uppercase()))
+                        .styled(style::Style::new().bold().with_font_size(11)));
+                }
+                if let Some(lvl)=ls.levels.get(&i){
+                    let mut spells = lvl.spells().clone();
+                    spells.sort();
+                    let mut spl_table= elements::TableLayout::new(vec![1,11,3,4,5,2,4,11]);
+                    for spl in spells{
+                        row_from_spell(&mut spl_table, &spl,symbol)?;
+                    }
+                    doc.push(spl_table);
+                } else {
+                    doc.push(elements::Break::new(1.0).styled(style::Style::new().with_font_size(7)));
+                }
+            }
+        }
+        if options.spell_descriptions{
+            doc.push(elements::PageBreak::new());
+            doc.push(spell_descriptions(&character.spell_lists));
+        }
+    }
+    if options.feature_descriptions{
+        doc.push(elements::PageBreak::new());
+        doc.push(feature_descriptions(character));
+    }
+    Ok(())
+}
+/// the full text of every feature, grouped by race, class, background and feat, then by name.
+/// The background feature goes with the other background features
+fn feature_descriptions(character: &Character)->elements::LinearLayout{
+    let mut features: Vec<&Feature> = character.features.iter().collect();
+    let background = character.background.background_feature();
+    if !background.name().is_empty(){
+        features.push(background);
+    }
+    features.sort_by(|a, b| a.source().cmp(b.source()).then_with(|| a.name().cmp(b.name())));
+    let bold = style::Style::new().bold();
+    let small = style::Style::new().with_font_size(8);
+    let mut layout = elements::LinearLayout::vertical()
+        .element(Paragraph::new("FEATURES & TRAITS").styled(bold))
+        .element(elements::Break::new(0.5));
+    let mut source = None;
+    for feat in features{
+        if source!=Some(feat.source()){
+            source = Some(feat.source());
+            layout.push(Paragraph::new(feat.source().to_string().to_uppercase()).styled(bold.with_font_size(10)));
+            layout.push(Hline::new());
+        }
+        let uses = match feat.uses(){
+            -1=>String::new(),
+            1=>"  1 use".to_string(),
+            n=>format!("  {} uses",n),
+        };
+        let mut entry = elements::LinearLayout::vertical()
+            .element(Paragraph::default().styled_string(feat.name(), bold).string(uses).styled(style::Style::new().with_font_size(10)));
+        if !feat.summary().is_empty() && feat.summary()!=feat.description(){
+            entry.push(Paragraph::new(feat.summary().replace("**","")).styled(small.italic()));
+        }
+        for line in feat.description().lines().map(str::trim).filter(|line| !line.is_empty()){
+            entry.push(Paragraph::new(line.replace("**","")).styled(small));
+        }
+        layout.push(entry.element(elements::Break::new(0.5)));
+    }
+    layout
+}
+/// the full text of every spell on the lists, by level then name. Spells on more than one list are printed once
+fn spell_descriptions(spell_lists: &[SpellList])->elements::LinearLayout{
+    let mut spells: Vec<&Spell> = spell_lists.iter().flat_map(|ls| ls.levels.values()).flat_map(|lvl| lvl.spells().iter()).collect();
+    spells.sort_by(|a, b| a.level().cmp(&b.level()).then_with(|| a.name().cmp(b.name())));
+    spells.dedup_by(|a, b| a.name()==b.name());
+    let bold = style::Style::new().bold();
+    let small = style::Style::new().with_font_size(8);
+    let mut layout = elements::LinearLayout::vertical()
+        .element(Paragraph::new("SPELL DESCRIPTIONS").styled(style::Style::new().bold()))
+        .element(elements::Break::new(0.5));
+    for spl in spells{
+        let level = level_and_school(spl);
+        let (verbal, somatic, concentration, ritual) = spl.vscr();
+        let mut components: Vec<String> = vec![];
+        if verbal {components.push("V".to_string());}
+        if somatic {components.push("S".to_string());}
+        if !spl.material().is_empty() {components.push(format!("M ({})",spl.material()));}
+        let casting_time = casting_time_text(spl.casting_time());
+        let mut details = format!("Casting time: {}{}    Range: {}    Components: {}    Duration: {}{}",
+            casting_time,if ritual {" (ritual)"} else {""},spl.range(),components.join(", "),
+            if concentration {"concentration, "} else {""},spl.duration());
+        if !spl.save().is_empty(){
+            details += &format!("    Save: {}",spl.save());
+        }
+        if !spl.damage().is_empty(){
+            details += &format!("    Damage: {}",spl.damage().join(", "));
+        }
+        let mut entry = elements::LinearLayout::vertical()
+            .element(Paragraph::default().styled_string(spl.name(), bold).string(format!("  {}",level)).styled(style::Style::new().with_font_size(10)))
+            .element(Paragraph::new(details).styled(small));
+        for line in spl.description().lines().map(str::trim).filter(|line| !line.is_empty()){
+            entry.push(Paragraph::new(line.replace("**","")).styled(small));
+        }
+        if !spl.higher_levels().is_empty(){
+            entry.push(Paragraph::default().styled_string("At Higher Levels. ", bold).string(spl.higher_levels().replace("**","")).styled(small));
+        }
+        layout.push(entry.element(elements::Break::new(0.5)));
+    }
+    layout
+}
+/// the weight carried and the carrying capacity, under the coins. With the variant rule the thresholds where the
+/// character becomes encumbered and heavily encumbered too, and a note when they carry too much
+fn encumbrance_boxes(character: &Character, variant: bool)->elements::LinearLayout{
+    let mut layout = elements::LinearLayout::vertical();
+    let Some(encumbrance)=character.encumbrance() else {return layout};
+    let label = style::Style::new().bold().with_font_size(7);
+    let mut boxes = vec![("CARRIED",encumbrance.carried),("CAPACITY",encumbrance.capacity)];
+    if variant{
+        boxes.push(("ENCUMB.",encumbrance.encumbered));
+        boxes.push(("HEAVY",encumbrance.heavily_encumbered));
+    }
+    //the column is as narrow as the coin boxes, so the unit goes in a heading instead of every box
+    layout.push(elements::Break::new(0.5));
+    layout.push(Paragraph::new("WEIGHT (LB)").aligned(Alignment::Center).styled(style::Style::new().with_font_size(5)));
+    for (name, pounds) in boxes{
+        layout.push(Paragraph::new(name).aligned(Alignment::Center).styled(label));
+        layout.push(Paragraph::new(trim_number(pounds)).aligned(Alignment::Center).padded(1).framed().padded(1));
+        layout.push(elements::Break::new(0.5));
+    }
+    let status = encumbrance.status(variant);
+    if status!="unencumbered"{
+        layout.push(Paragraph::new(status).aligned(Alignment::Center).styled(style::Style::new().italic().with_font_size(7)));
+    }
+    layout
+}
+/// the items carried loose, then each container with the items in it. Containers come after the one they are in,
+/// any caught in a loop of containers holding each other are left out
+pub(crate) fn equipment_groups(character: &Character)->Vec<(Option<&Container>,Vec<&Item>)>{
+    fn add<'a>(character: &'a Character, container: Option<&'a Container>, groups: &mut Vec<(Option<&'a Container>,Vec<&'a Item>)>){
+        let id = container.map(|cnt| cnt.id().as_str()).unwrap_or("");
+        let mut items: Vec<&Item> = character.equipment.iter().filter(|itm| itm.container()==id).collect();
+        items.sort();
+        groups.push((container, items));
+        let mut inside: Vec<&Container> = character.containers.iter().filter(|cnt| cnt.container()==id).collect();
+        inside.sort();
+        for cnt in inside{
+            add(character, Some(cnt), groups);
+        }
+    }
+    let mut groups = vec![];
+    add(character, None, &mut groups);
+    groups
+}
+/// 1st, 2nd, 3rd, 4th...
+pub(crate) fn ordinal(n: i64)->String{
+    let suffix = match n{1=>"st",2=>"nd",3=>"rd",_=>"th"};
+    format!("{}{}",n,suffix)
+}
+/// eg `5 lb` or `0.25 lb`
+pub(crate) fn weight_text(pounds: f64)->String{
+    format!("{} lb",trim_number(pounds))
+}
+/// eg `12 gp` or `0.05 gp`
+pub(crate) fn value_text(gold: f64)->String{
+    format!("{} gp",trim_number(gold))
+}
+fn trim_number(num: f64)->String{
+    let text = format!("{:.2}",num);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+/// whether the item is equipped or attuned, eg ` (equipped, attuned)`, or "" if neither
+pub(crate) fn item_status(itm: &Item)->String{
+    let status: Vec<&str> = [(itm.equipped(),"equipped"),(itm.attuned(),"attuned")].into_iter()
+        .filter(|(on, _)| *on).map(|(_, text)| text).collect();
+    if status.is_empty() {String::new()} else {format!(" ({})",status.join(", "))}
+}
+/// eg `1st level evocation` or `evocation cantrip`
+pub(crate) fn level_and_school(spl: &Spell)->String{
+    match spl.level(){
+        0=>format!("{} cantrip",spl.school()),
+        lvl=>format!("{} level {}",ordinal(lvl),spl.school()),
+    }
+}
+/// the casting time spelled out, the spell table abbreviates it
+pub(crate) fn casting_time_text(casting_time: &ActionType)->String{
+    match casting_time{
+        ActionType::Action=>"1 action".to_string(),
+        ActionType::Bonus=>"1 bonus action".to_string(),
+        ActionType::Reaction=>"1 reaction".to_string(),
+        ActionType::Free=>"free".to_string(),
+        ActionType::Long(time)=>time.clone(),
+    }
+}
+fn row_from_spell(spell_table: &mut elements::TableLayout, spl: &Spell, symb: style::Style)->Result<(),Error>{
+    let scl: String = spl.school().chars().take(4).collect();
+    let material: String = spl.material().chars().take(30).collect();
+    let sty = style::Style::new().with_font_size(10);
+    let prpd = match spl.prepd(){
+        SpellPrep::AlwaysPrepared => Paragraph::new("A ").styled(sty),
+        _=> Paragraph::default().styled_string("⭘",symb.with_font_size(10)).string(" ").styled(sty)
+    };
+    spell_table
+        .row()
+        .element(prpd)
+        .element(Paragraph::new(spl.name()).styled(sty))
+        .element(Paragraph::new(format!("{}.",scl)).styled(sty))
+        .element(Paragraph::new(spl.casting_time().to_string()).styled(sty))
+        .element(Paragraph::new(spl.range()).styled(sty))
+        .element(Paragraph::new(spl.vscr_to_string()).styled(sty))
+        .element(Paragraph::new(spl.duration()).styled(sty))
+        .element(Paragraph::new(material).styled(sty))
+        .push()
+}
+///the box for one ability score, left blank if the character doesn't have it
+fn element_from_score(name: &str, score: Option<&AbilityScore>)->elements::LinearLayout{
+    elements::LinearLayout::vertical()
+        .element(
+            Paragraph::new(name.to_uppercase())
+            .aligned(Alignment::Center)
+            .styled(style::Style::new().bold().with_font_size(7))
+        )
+        .element(
+            Paragraph::new(score.map(|score| bns_translator(score.modifier())).unwrap_or_default())
+                .aligned(Alignment::Center)
+                .styled(style::Style::new().with_font_size(20))
+        )
+        .element(
+            Paragraph::new(score.map(|score| score.score().to_string()).unwrap_or_default())
+                .aligned(Alignment::Center)
+                .styled(style::Style::new().with_font_size(7))
+                .framed()
+        )
+}
+fn element_from_skill(skill: &Skill, symb_fnt: &style::Style)->elements::StyledElement<Paragraph>{
+    let mut bns = bns_translator(skill.modifier());
+    if bns.len() == 2 {
+        bns=String::from(" ")+&bns;
+    }
+    Paragraph::default()
+        .styled_string(proficiency_translator(skill.prof()),*symb_fnt)
+        .string(format!(" {}  {}",bns,skill.name()))
+        .styled(style::Style::new().with_font_size(8))
+}
+///the symbol the sheets mark a proficiency with
+pub(crate) fn proficiency_translator(prof: &Proficiency)->String{
+    match prof{
+        Proficiency::None => String::from("⭘"),
+        Proficiency::Half => String::from("◐"),
+        Proficiency::Profficient => String::from("⦿"),
+        Proficiency::Expert => String::from("❂")
+    }
+}
+/// pads a String vertically(ie with new lines) to lines long, assuming a line width of `width` characters(under worst case)
+fn vertical_pad(txt: String, width: usize, lines: usize)->elements::LinearLayout{
+    let mut wrapped= textwrap::wrap(&txt,width).into_iter();
+    let mut out = elements::LinearLayout::vertical();
+    for _idx in 0..lines{
+        if let Some(mut thing)=wrapped.next(){
+            out=out.element(Paragraph::new(thing.to_mut().as_str()).aligned(Alignment::Center)
+                .styled(style::Style::new().with_font_size(10)));
+        } else {
+            out=out.element(elements::Break::new(1.0).styled(style::Style::new().with_font_size(10)));
+        }
+    }
+    out
+}
+fn spell_slot_elem(spell_slots: &[i64],level: i64, symbol: style::Style, slt: style::Style)-> elements::LinearLayout{
+    elements::LinearLayout::vertical()
+        .element(Paragraph::new(format!("{} LEVEL",ordinal(level).to_uppercase())).aligned(Alignment::Center).styled(slt))
+        .element(Paragraph::new(vec!["⭘"; spell_slots[(level-1) as usize].max(0) as usize].into_iter().collect::<String>())
+            .aligned(Alignment::Center).styled(symbol.with_font_size(10)))
+}
@@ -0,0 +1,169 @@
+//! Spell cards: every spell on the character's spell lists on its own poker sized card(2.5 by 3.5 inches),
+//! nine to a letter page with cut lines between them
+use genpdf::{Element, Alignment, Mm, Position, Size, RenderResult, Context};
+use genpdf::{elements::{self,Paragraph},fonts,style,render::Area};
+use genpdf::error::Error;
+use crate::holding_structs::*;
+use crate::bns_translator;
+use crate::sheet::{casting_time_text,level_and_school};
+
+///the size of a card in millimeters
+const CARD_WIDTH: f64 = 63.5;
+const CARD_HEIGHT: f64 = 88.9;
+const COLUMNS: usize = 3;
+const ROWS: usize = 3;
+
+/// builds the spell cards for every spell of the character, by spell list, then level, then name.
+/// Needs the fonts in ./fonts, like `generate_pdf`
+/// #Example
+/// ```no_run
+/// use dicecloud_sheet_printer::holding_structs::Character;
+/// use dicecloud_sheet_printer::spell_cards::render_spell_cards;
+///
+/// # let character = Character::default();
+/// render_spell_cards(&character).unwrap().render_to_file("spell_cards.pdf").unwrap();
+/// ```
+/// #Errors
+/// fails if the font can't be loaded
+pub fn render_spell_cards(character: &Character)->Result<genpdf::Document,Error>{
+    let font = fonts::from_files("./fonts/Roboto","Roboto",None)?;
+    let mut doc = genpdf::Document::new(font);
+    doc.set_title(format!("{} Spell Cards",character.char_name));
+    doc.set_minimal_conformance();
+    doc.set_line_spacing(1.1);
+    doc.set_paper_size(genpdf::PaperSize::Letter);
+    let mut decorator = genpdf::SimplePageDecorator::new();
+    //center the 3 by 3 grid on the page
+    decorator.set_margins(genpdf::Margins::vh(6, 12.7));
+    doc.set_page_decorator(decorator);
+    let mut spell_lists = character.spell_lists.clone();
+    spell_lists.sort();
+    let mut cards = Vec::new();
+    for ls in spell_lists.iter(){
+        let mut levels: Vec<&SpellLevel> = ls.levels.values().collect();
+        levels.sort_by_key(|lvl| lvl.lvl());
+        for lvl in levels{
+            let mut spells = lvl.spells().clone();
+            spells.sort_by(|a, b| a.name().cmp(b.name()));
+            cards.extend(spells.into_iter().map(|spell| SpellCard{spell, list: ls.clone()}));
+        }
+    }
+    if cards.is_empty(){
+        doc.push(Paragraph::new(format!("{} has no spells",character.char_name)));
+    }
+    let mut pages = cards.chunks(COLUMNS*ROWS).peekable();
+    while let Some(page) = pages.next(){
+        doc.push(CardPage{cards: page.to_vec()});
+        if pages.peek().is_some(){
+            doc.push(elements::PageBreak::new());
+        }
+    }
+    Ok(doc)
+}
+///a spell, with the list it is cast from for the save dc and attack bonus
+#[derive(Clone)]
+struct SpellCard{
+    spell: Spell,
+    list: SpellList,
+}
+impl SpellCard{
+    ///everything on the card but the footer, which goes at the bottom
+    fn body(&self)->elements::LinearLayout{
+        let spl = &self.spell;
+        let label = style::Style::new().bold().with_font_size(5);
+        let value = style::Style::new().with_font_size(7);
+        let prepared = match spl.prepd(){
+            SpellPrep::AlwaysPrepared=>"ALWAYS PREPARED",
+            SpellPrep::Prepared=>"PREPARED",
+            SpellPrep::NotPrepared=>"",
+        };
+        let (verbal, somatic, concentration, ritual) = spl.vscr();
+        let mut flags = vec![level_and_school(spl)];
+        if concentration {flags.push("concentration".to_string());}
+        if ritual {flags.push("ritual".to_string());}
+        let mut components: Vec<&str> = vec![];
+        if verbal {components.push("V");}
+        if somatic {components.push("S");}
+        if !spl.material().is_empty() {components.push("M");}
+        let stat = |name: &str, val: String| elements::LinearLayout::vertical()
+            .element(Paragraph::new(name).styled(label))
+            .element(Paragraph::new(val).styled(value));
+        let mut stats = elements::TableLayout::new(vec![1,1]);
+        stats.row()
+            .element(stat("CASTING TIME", casting_time_text(spl.casting_time())))
+            .element(stat("RANGE", spl.range().clone()))
+            .push().expect("failed to add row");
+        stats.row()
+            .element(stat("COMPONENTS", components.join(", ")))
+            .element(stat("DURATION", spl.duration().clone()))
+            .push().expect("failed to add row");
+        let mut body = elements::LinearLayout::vertical()
+            .element(Paragraph::new(prepared).aligned(Alignment::Right).styled(label))
+            .element(Paragraph::new(spl.name()).styled(style::Style::new().bold().with_font_size(9)))
+            .element(Paragraph::new(flags.join(", ")).styled(style::Style::new().italic().with_font_size(6)))
+            .element(elements::Break::new(0.3))
+            .element(stats);
+        let text = style::Style::new().with_font_size(6);
+        if !spl.material().is_empty(){
+            body.push(Paragraph::new(format!("M: {}",spl.material())).styled(text.italic()));
+        }
+        let mut extra = vec![];
+        if !spl.save().is_empty(){
+            extra.push(format!("{} save",spl.save()));
+        }
+        if !spl.damage().is_empty(){
+            extra.push(spl.damage().join(", "));
+        }
+        if !extra.is_empty(){
+            body.push(Paragraph::new(extra.join("    ")).styled(style::Style::new().bold().with_font_size(6)));
+        }
+        body.push(Hline::new());
+        //long descriptions get a smaller font so more of them fits
+        let len = spl.description().len()+spl.higher_levels().len();
+        let text = if len>1200 {text.with_font_size(5)} else {text};
+        for line in spl.description().lines().map(str::trim).filter(|line| !line.is_empty()){
+            body.push(Paragraph::new(line.replace("**","")).styled(text));
+        }
+        if !spl.higher_levels().is_empty(){
+            body.push(Paragraph::default().styled_string("At Higher Levels. ", text.bold())
+                .string(spl.higher_levels().replace("**","")).styled(text));
+        }
+        body
+    }
+    fn render(&self, context: &Context, mut area: Area<'_>, style: style::Style)->Result<(),Error>{
+        area.add_margins(genpdf::Margins::all(3));
+        let footer_height = Mm::from(3.5);
+        let mut body_area = area.clone();
+        body_area.set_height(area.size().height-footer_height);
+        let cut_short = self.body().render(context, body_area, style)?.has_more;
+        let mut footer = format!("{}    DC {}    {}",self.list.name,self.list.save_dc,bns_translator(self.list.atk_bonus));
+        if cut_short{
+            footer += "    (continued in the rulebook)";
+        }
+        area.add_offset(Position::new(0, area.size().height-footer_height));
+        Paragraph::new(footer).styled(style::Style::new().with_font_size(5)).render(context, area, style)?;
+        Ok(())
+    }
+}
+///up to nine cards in a grid, with lines around each card to cut along
+struct CardPage{
+    cards: Vec<SpellCard>,
+}
+impl Element for CardPage{
+    fn render(&mut self, context: &Context, area: Area<'_>, style: style::Style)->Result<RenderResult,Error>{
+        let (width, height) = (Mm::from(CARD_WIDTH), Mm::from(CARD_HEIGHT));
+        for (i, card) in self.cards.iter().enumerate(){
+            let mut card_area = area.clone();
+            card_area.add_offset(Position::new(width*(i%COLUMNS) as f64, height*(i/COLUMNS) as f64));
+            card_area.set_size(Size::new(width, height));
+            //neighbouring cards share their edges, so this draws a single line to cut along between them
+            let (zero, corner) = (Mm::from(0), Position::new(width, height));
+            card_area.draw_line(vec![Position::new(zero, zero), Position::new(corner.x, zero), corner,
+                Position::new(zero, corner.y), Position::new(zero, zero)], style::Style::new());
+            card.render(context, card_area, style)?;
+        }
+        let rows = self.cards.len().div_ceil(COLUMNS);
+        let columns = self.cards.len().min(COLUMNS);
+        Ok(RenderResult{size: Size::new(width*columns as f64, height*rows as f64), has_more: false})
+    }
+}
@@ -0,0 +1,272 @@
+//! Renders a `Character` as a markdown or plain text sheet, for pasting into chat, forums or wikis.
+//! Both cover the same sections as the pdf and only use ascii markers, so they survive any font
+use crate::bns_translator;
+use crate::holding_structs::*;
+use crate::sheet::{equipment_groups,item_status,ordinal,value_text,weight_text};
+use std::fmt::Write;
+
+/// renders the character as markdown, with headings and tables
+/// #Example
+/// ```
+/// use dicecloud_sheet_printer::text_sheet::render_markdown;
+/// use dicecloud_sheet_printer::holding_structs::{Character, Class};
+///
+/// let mut character = Character::default();
+/// character.char_name = "Test".to_string();
+/// character.classes.push(Class::new("Wizard".to_string(), 3));
+/// let sheet = render_markdown(&character);
+/// assert!(sheet.starts_with("# Test\n"));
+/// assert!(sheet.contains("Wizard 3"));
+/// ```
+pub fn render_markdown(character: &Character)->String{
+    render(character, Flavor::Markdown)
+}
+/// renders the character as plain text with the columns lined up, for monospace fonts
+/// #Example
+/// ```
+/// use dicecloud_sheet_printer::text_sheet::render_text;
+/// use dicecloud_sheet_printer::holding_structs::Character;
+///
+/// let mut character = Character::default();
+/// character.char_name = "Test".to_string();
+/// let sheet = render_text(&character);
+/// assert!(sheet.starts_with("TEST\n====\n"));
+/// ```
+pub fn render_text(character: &Character)->String{
+    render(character, Flavor::Text)
+}
+#[derive(Clone,Copy,PartialEq,Eq)]
+enum Flavor{
+    Markdown,
+    Text,
+}
+///writes headings, lines and tables in either flavor
+struct SheetWriter{
+    flavor: Flavor,
+    out: String,
+}
+impl SheetWriter{
+    fn heading(&mut self, level: usize, text: &str){
+        if !self.out.is_empty() && !self.out.ends_with("\n\n"){
+            self.out.push('\n');
+        }
+        match self.flavor{
+            Flavor::Markdown=>{
+                let _ = writeln!(self.out,"{} {}","#".repeat(level),text);
+            },
+            Flavor::Text=>{
+                let text = if level<3 {text.to_uppercase()} else {text.to_string()};
+                let underline = match level{1=>"=",2=>"-",_=>"~"};
+                let _ = writeln!(self.out,"{}\n{}",text,underline.repeat(text.chars().count()));
+            }
+        }
+    }
+    fn line(&mut self, text: &str){
+        match self.flavor{
+            //two trailing spaces keep consecutive lines apart in markdown
+            Flavor::Markdown=>{
+                let _ = writeln!(self.out,"{}  ",text.trim_end());
+            },
+            Flavor::Text=>{
+                let _ = writeln!(self.out,"{}",text.trim_end());
+            }
+        }
+    }
+    fn table(&mut self, header: &[&str], rows: &[Vec<String>]){
+        match self.flavor{
+            //tables need a blank line on both sides so they aren't read as part of a paragraph
+            Flavor::Markdown=>{
+                if !self.out.ends_with("\n\n") && !self.out.lines().last().is_some_and(|l| l.starts_with('#')){
+                    self.out.push('\n');
+                }
+                let _ = writeln!(self.out,"| {} |",header.join(" | "));
+                let _ = writeln!(self.out,"|{}",vec!["---|"; header.len()].concat());
+                for row in rows{
+                    let cells: Vec<String> = row.iter().map(|cell| cell.replace('|',"\\|")).collect();
+                    let _ = writeln!(self.out,"| {} |",cells.join(" | "));
+                }
+                self.out.push('\n');
+            },
+            Flavor::Text=>{
+                let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+                for row in rows{
+                    for (width, cell) in widths.iter_mut().zip(row){
+                        *width = (*width).max(cell.chars().count());
+                    }
+                }
+                let header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+                self.text_row(&header, &widths);
+                let dashes: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+                self.text_row(&dashes, &widths);
+                for row in rows{
+                    self.text_row(row, &widths);
+                }
+            }
+        }
+    }
+    fn text_row(&mut self, row: &[String], widths: &[usize]){
+        let cells: Vec<String> = row.iter().zip(widths).map(|(cell, width)| format!("{:<width$}",cell,width=*width)).collect();
+        let _ = writeln!(self.out,"{}",cells.join("  ").trim_end());
+    }
+}
+fn render(character: &Character, flavor: Flavor)->String{
+    let mut w = SheetWriter{flavor, out: String::new()};
+    let mut classes = character.classes.clone();
+    classes.sort();
+    let class_str = classes.iter().map(|class| format!("{} {}",class.name(),class.level())).collect::<Vec<_>>().join(" / ");
+    w.heading(1,&character.char_name);
+    w.line(&[class_str.as_str(),character.race.as_str(),character.background.name().as_str(),character.alignment.as_str()]
+        .into_iter().filter(|part| !part.is_empty()).collect::<Vec<_>>().join(", "));
+    if character.xp>0{
+        w.line(&format!("XP: {}",character.xp));
+    }
+    w.line(&format!("AC {} | Initiative {} | Speed {} | HP {} | Proficiency {} | Passive Perception {}",
+        character.ac,bns_translator(character.initiative),character.speed,character.hit_points,
+        bns_translator(character.prof_bonus),character.passive_perception()));
+    if !character.hit_dice.is_empty(){
+        w.line(&format!("Hit Dice: {}",character.hit_dice.iter().map(|die| die.to_string()).collect::<Vec<_>>().join(", ")));
+    }
+
+    w.heading(2,"Ability Scores");
+    let scores: Vec<Vec<String>> = character.ability_scores.iter()
+        .map(|score| vec![score.name().clone(),score.score().to_string(),bns_translator(score.modifier())]).collect();
+    w.table(&["Ability","Score","Mod"],&scores);
+
+    w.heading(2,"Saving Throws");
+    let saves: Vec<Vec<String>> = character.saving_throws.iter().map(skill_row).collect();
+    w.table(&["Prof","Save","Mod"],&saves);
+
+    w.heading(2,"Skills");
+    let mut skills = character.skills.clone();
+    skills.sort();
+    let skills: Vec<Vec<String>> = skills.iter().map(skill_row).collect();
+    w.table(&["Prof","Skill","Mod"],&skills);
+    w.line("Proficiency: [ ] none, [/] half, [x] proficient, [E] expert");
+
+    let (armor, weapons, languages, tools) = &character.other_profs;
+    w.heading(2,"Other Proficiencies & Languages");
+    w.line(&format!("Armor: {}",armor.join(", ")));
+    w.line(&format!("Weapons: {}",weapons.join(", ")));
+    w.line(&format!("Languages: {}",languages.join(", ")));
+    w.line(&format!("Tools: {}",tools.join(", ")));
+
+    if !character.attacks.is_empty(){
+        w.heading(2,"Attacks");
+        let mut attacks = character.attacks.clone();
+        attacks.sort();
+        let attacks: Vec<Vec<String>> = attacks.iter()
+            .map(|atk| vec![atk.name().clone(),atk.bonus_as_string(),atk.damage().clone()]).collect();
+        w.table(&["Name","Atk Bonus","Damage/Type"],&attacks);
+    }
+
+    let mut actions = character.actions.clone();
+    actions.sort();
+    if !actions.is_empty(){
+        w.heading(2,"Actions");
+        for act in actions.iter(){
+            w.line(&format!("- {}",act));
+        }
+    }
+    let mut resources = character.resources.clone();
+    resources.sort();
+    let mut dmg_mults = character.damage_mults.clone();
+    dmg_mults.sort();
+    let mut features = character.features.clone();
+    features.sort();
+    let feature_lines: Vec<String> = resources.iter().map(|r| r.to_string())
+        .chain(dmg_mults.iter().map(|mul| mul.to_string()))
+        .chain(features.into_iter().filter(|feat| !actions.iter().any(|x| feat.name()==x.name())).map(|feat| feat.to_string()))
+        .collect();
+    if !feature_lines.is_empty(){
+        w.heading(2,"Features & Traits");
+        for feat in feature_lines{
+            w.line(&format!("- {}",feat));
+        }
+    }
+
+    let (personality, ideals, bonds, flaws) = &character.traits;
+    if [personality, ideals, bonds, flaws].iter().any(|t| !t.is_empty()){
+        w.heading(2,"Personality");
+        for (name, text) in [("Personality Traits",personality),("Ideals",ideals),("Bonds",bonds),("Flaws",flaws)]{
+            if !text.is_empty(){
+                w.line(&format!("{}: {}",name,text));
+            }
+        }
+    }
+    let feature = character.background.background_feature();
+    if !feature.name().is_empty(){
+        w.heading(2,&format!("Background: {}",character.background.name()));
+        w.line(feature.name());
+        w.line(feature.summary());
+    }
+
+    w.heading(2,"Equipment");
+    let (cp, sp, ep, gp, pp) = character.coins;
+    w.line(&format!("CP {} | SP {} | EP {} | GP {} | PP {}",cp,sp,ep,gp,pp));
+    for (container, items) in equipment_groups(character){
+        if let Some(cnt)=container{
+            let inside = character.container(cnt.container()).map(|outer| format!(" (in {})",outer.name())).unwrap_or_default();
+            let weightless = if cnt.contents_weightless() {", weightless"} else {""};
+            w.heading(3,&format!("{}{}: {}{}, {}",cnt.name(),inside,weight_text(character.contents_weight(cnt.id())),weightless,
+                value_text(character.contents_value(cnt.id()))));
+        }
+        if items.is_empty(){
+            continue;
+        }
+        let rows: Vec<Vec<String>> = items.iter().map(|itm| {
+            let nme = if itm.quantity()==1 {itm.name()} else {itm.plural_name()};
+            let atn = if itm.requires_attunement() {"(A) "} else {""};
+            vec![itm.quantity().to_string(),format!("{}{}{}",atn,nme,item_status(itm)),weight_text(itm.total_weight()),value_text(itm.total_value())]
+        }).collect();
+        w.table(&["Qty","Item","Weight","Value"],&rows);
+    }
+    if !character.equipment.is_empty() || !character.containers.is_empty(){
+        w.line(&format!("Total: {}, {}",weight_text(character.contents_weight("")),value_text(character.contents_value(""))));
+    }
+    if character.equipment.iter().any(|itm| itm.requires_attunement()){
+        w.line("(A) requires attunement");
+    }
+
+    let mut spell_lists = character.spell_lists.clone();
+    spell_lists.sort();
+    if !spell_lists.is_empty(){
+        w.heading(2,"Spells");
+        let slots: Vec<String> = character.spell_slots.iter().enumerate().filter(|(_, num)| **num>0)
+            .map(|(idx, num)| format!("{} {}",ordinal(idx as i64+1),num)).collect();
+        if !slots.is_empty(){
+            w.line(&format!("Spell slots: {}",slots.join(", ")));
+        }
+        for ls in spell_lists.iter(){
+            w.heading(3,&ls.name);
+            w.line(&format!("Attack {} | Save DC {} | Prepared __/{}",bns_translator(ls.atk_bonus),ls.save_dc,ls.max_prepared));
+            for lvl in 0..=ls.max_lvl(){
+                let Some(spell_level)=ls.levels.get(&lvl) else {continue};
+                let title = if lvl==0 {"Cantrips".to_string()} else {format!("{} Level",ordinal(lvl))};
+                w.heading(4,&title);
+                let mut spells = spell_level.spells().clone();
+                spells.sort();
+                let rows: Vec<Vec<String>> = spells.iter().map(|spl| {
+                    let prepd = match spl.prepd(){
+                        SpellPrep::AlwaysPrepared=>"[A]",
+                        SpellPrep::Prepared=>"[x]",
+                        SpellPrep::NotPrepared=>"[ ]",
+                    };
+                    vec![prepd.to_string(),spl.name().clone(),spl.school().clone(),spl.casting_time().to_string(),
+                        spl.range().clone(),spl.vscr_to_string().to_uppercase(),spl.duration().clone(),spl.material().clone()]
+                }).collect();
+                w.table(&["P","Name","School","Time","Range","VSCR","Duration","Material"],&rows);
+            }
+        }
+        w.line("P: [A] always prepared, [x] prepared, [ ] not prepared");
+    }
+    w.out
+}
+fn skill_row(skill: &Skill)->Vec<String>{
+    let prof = match skill.prof(){
+        Proficiency::None=>"[ ]",
+        Proficiency::Half=>"[/]",
+        Proficiency::Profficient=>"[x]",
+        Proficiency::Expert=>"[E]",
+    };
+    vec![prof.to_string(),skill.name().clone(),bns_translator(skill.modifier())]
+}
@@ -0,0 +1,74 @@
+use crate::cache::Cache;
+use crate::cli::{Args,OutputFormat};
+use crate::{parse_character,print_warnings,select_attacks,write_sheet};
+use dicecloud_sheet_printer::{DicecloudClient,holding_structs::{Attack,Character},sheet::ATTACK_ROWS};
+use serde_json::Value;
+use std::time::Duration;
+
+///The sheet printed last, and the choices made for it
+pub struct Printed{
+    pub character: Character,
+    pub include_xp: bool,
+    pub attacks: Option<Vec<Attack>>,
+    pub out_path: String,
+}
+///checks the character every `--watch-interval` seconds and prints it again to the same file whenever it changed.
+///Changes are found by comparing the parsed character, so edits that don't show on the sheet don't trigger a print.
+///Runs until the program is stopped, failed checks are reported and retried at the next interval
+pub async fn run(args: &Args, client: &DicecloudClient, id: &str, race_decoder: &Value, mut last: Printed){
+    println!("Watching {} for changes every {} seconds, press ctrl+c to stop",last.character.char_name,args.watch_interval);
+    let cache = Cache::open();
+    loop{
+        tokio::time::sleep(Duration::from_secs(args.watch_interval)).await;
+        let char_json = match client.creature(id).await{
+            Ok(json)=>json,
+            Err(e)=>{
+                println!("Failed to check for changes: {}",e);
+                continue;
+            }
+        };
+        if let Some(cache)=&cache{
+            if let Err(e)=cache.store_creature(id, &char_json){
+                println!("Failed to cache {}: {}",id,e);
+            }
+        }
+        let (character, warnings) = match parse_character(args, char_json, race_decoder.clone()){
+            Ok(parsed)=>parsed,
+            Err(e)=>{
+                println!("Failed to read the changed character: {}",e);
+                continue;
+            }
+        };
+        if character==last.character{
+            continue;
+        }
+        println!("{} changed, printing again",character.char_name);
+        let attacks = match reselect_attacks(args, &last, &character){
+            Ok(attacks)=>attacks,
+            Err(e)=>{
+                println!("{}, not printing this change",e);
+                continue;
+            }
+        };
+        match write_sheet(args, client, &character, last.include_xp, attacks.clone(), &last.out_path).await{
+            Ok(())=>println!("Wrote {}",last.out_path),
+            Err(e)=>println!("{}",e),
+        }
+        print_warnings(&warnings);
+        last.character = character;
+        last.attacks = attacks;
+    }
+}
+///the attacks to print after a change, without prompting. All of them if they fit or the ones from `--attack`,
+///otherwise the ones picked last time that the character still has
+fn reselect_attacks(args: &Args, last: &Printed, character: &Character)->Result<Option<Vec<Attack>>,String>{
+    if args.format!=OutputFormat::Pdf{
+        return Ok(None);
+    }
+    match &last.attacks{
+        Some(picked) if args.attacks.is_empty() && character.attacks.len()>ATTACK_ROWS=>{
+            Ok(Some(picked.iter().filter_map(|atk| character.attacks.iter().find(|new| new.name()==atk.name()).cloned()).collect()))
+        },
+        _=>select_attacks(args, false, character).map(Some),
+    }
+}